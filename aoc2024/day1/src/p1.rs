@@ -8,7 +8,7 @@ mod parse;
 
 #[cfg_attr(test, allow(unused))]
 fn main() {
-    util::DayInput::find::<1>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 fn solve(input: impl AsRef<str>) -> u32 {
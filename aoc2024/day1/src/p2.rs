@@ -11,7 +11,7 @@ use winnow::{ascii::*, combinator::*, error::*, prelude::*, token::*, Parser};
 
 #[cfg_attr(test, allow(unused))]
 fn main() {
-    util::DayInput::find::<1>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 fn solve(input: impl AsRef<str>) -> u32 {
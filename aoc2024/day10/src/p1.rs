@@ -16,7 +16,7 @@ mod common;
 use common::*;
 
 fn main() {
-    util::DayInput::find::<10>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Definitions
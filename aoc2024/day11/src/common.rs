@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use winnow::{ascii::dec_uint, combinator::separated, PResult, Parser};
 
 #[derive(Debug, Copy, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
@@ -15,3 +17,243 @@ pub fn parse_stones(input: &mut &str) -> PResult<Stones> {
 fn parse_stone(input: &mut &str) -> PResult<Stone> {
     dec_uint.map(Stone).parse_next(input)
 }
+
+/// Blink count from `--blinks=N`, if given.
+pub fn blinks_from_args() -> Option<usize> {
+    util::flag_value("blinks")
+        .map(|blinks| blinks.parse().expect("blinks formatted like `--blinks=75`"))
+}
+
+/// What a stone turns into after one blink.
+pub fn process(stone: Stone) -> (Stone, Option<Stone>) {
+    match stone.0 {
+        // num is zero
+        0 => (Stone(1), None),
+        // if amount of digits in number is even
+        n if (n.ilog10() + 1).is_multiple_of(2) => {
+            let digit_len = n.ilog10() + 1;
+            let half_digit_len = digit_len / 2;
+            let cut_off: u64 = 10_u64.pow(half_digit_len);
+            let left_digits = n / cut_off;
+            let right_digits = n % cut_off;
+            (Stone(left_digits), Some(Stone(right_digits)))
+        }
+        // all other numbers
+        n => (Stone(n.checked_mul(2024).expect("stone fits u64")), None),
+    }
+}
+
+/// Every stone after one blink, in order, to check the counts against.
+#[cfg(test)]
+pub fn after_blink(stones: Stones) -> Stones {
+    Stones(
+        stones
+            .0
+            .into_iter()
+            .flat_map(|stone| {
+                match process(stone) {
+                    (l, Some(r)) => vec![l, r],
+                    (l, None) => vec![l],
+                }
+                .into_iter()
+            })
+            .collect(),
+    )
+}
+
+pub type StoneCount = u128;
+
+/// Amount of stones after blinking some times.
+/// Stones with the same value behave the same and their order doesn't matter for the
+/// amount, so only the amount of stones per value is kept.
+#[derive(Debug, Clone)]
+pub struct StoneCounts {
+    counts: HashMap<Stone, StoneCount>,
+    blinks: usize,
+    /// Every value any stone had so far.
+    seen: HashSet<Stone>,
+    /// First blink that didn't make any value not seen before.
+    settled_at: Option<usize>,
+    /// First blink after which there were more stones than a [`StoneCount`] holds.
+    /// Counts stop at [`StoneCount::MAX`] from then on.
+    overflowed_at: Option<usize>,
+}
+
+impl From<&Stones> for StoneCounts {
+    fn from(stones: &Stones) -> Self {
+        let mut counts = HashMap::new();
+        for &stone in &stones.0 {
+            *counts.entry(stone).or_default() += 1;
+        }
+        StoneCounts {
+            seen: counts.keys().copied().collect(),
+            counts,
+            blinks: 0,
+            settled_at: None,
+            overflowed_at: None,
+        }
+    }
+}
+
+impl StoneCounts {
+    pub fn blink(&mut self) {
+        let mut counts = HashMap::with_capacity(self.counts.len());
+        let mut overflowed = false;
+        let mut add = |stone: Stone, count: StoneCount| {
+            let total: &mut StoneCount = counts.entry(stone).or_default();
+            *total = total.checked_add(count).unwrap_or_else(|| {
+                overflowed = true;
+                StoneCount::MAX
+            });
+        };
+        for (&stone, &count) in &self.counts {
+            let (left, right) = process(stone);
+            add(left, count);
+            if let Some(right) = right {
+                add(right, count);
+            }
+        }
+        self.counts = counts;
+        self.blinks += 1;
+        if self.overflowed_at.is_none() && (overflowed || self.sum().is_none()) {
+            self.overflowed_at = Some(self.blinks);
+        }
+        let mut new_values = false;
+        for &stone in self.counts.keys() {
+            new_values |= self.seen.insert(stone);
+        }
+        if !new_values && self.settled_at.is_none() {
+            self.settled_at = Some(self.blinks);
+        }
+    }
+
+    pub fn blink_times(&mut self, blinks: usize) {
+        for _ in 0..blinks {
+            self.blink();
+        }
+    }
+
+    #[allow(dead_code)] // only p2 reports stats
+    pub fn blinks(&self) -> usize {
+        self.blinks
+    }
+
+    /// Amount of stones, unless there were too many to count.
+    pub fn total(&self) -> Option<StoneCount> {
+        match self.overflowed_at {
+            Some(_) => None,
+            None => self.sum(),
+        }
+    }
+
+    fn sum(&self) -> Option<StoneCount> {
+        self.counts
+            .values()
+            .try_fold(0 as StoneCount, |total, &count| total.checked_add(count))
+    }
+
+    /// Amount of different values of the stones right now.
+    #[allow(dead_code)] // only p2 reports stats
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Amount of different values any stone had so far.
+    #[allow(dead_code)] // only p2 reports stats
+    pub fn distinct_seen(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// First blink that didn't make any new value.
+    /// Every value a blink makes then comes from a value seen before, whose next values
+    /// were seen before as well, so no blink after it makes new values either.
+    #[allow(dead_code)] // only p2 reports stats
+    pub fn settled_at(&self) -> Option<usize> {
+        self.settled_at
+    }
+
+    #[allow(dead_code)] // only p2 reports stats
+    pub fn overflowed_at(&self) -> Option<usize> {
+        self.overflowed_at
+    }
+}
+
+/// Amount of stones after blinking `blinks` times, unless there are too many to count.
+#[allow(dead_code)] // p2 keeps the counts around for `--stats`
+pub fn total_after(stones: &Stones, blinks: usize) -> Option<StoneCount> {
+    let mut counts = StoneCounts::from(stones);
+    counts.blink_times(blinks);
+    counts.total()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Stones {
+        Stones(vec![Stone(125), Stone(17)])
+    }
+
+    #[test]
+    fn example_totals() {
+        let mut counts = StoneCounts::from(&example());
+        let totals = (0..6)
+            .map(|_| {
+                counts.blink();
+                counts.total()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [3, 4, 5, 9, 13, 22].map(Some));
+        assert_eq!(total_after(&example(), 25), Some(55312));
+        assert_eq!(total_after(&example(), 75), Some(65601038650482));
+    }
+
+    #[test]
+    fn ordered_stones_counted() {
+        let mut stones = example();
+        let mut counts = StoneCounts::from(&stones);
+        for _ in 0..15 {
+            stones = after_blink(stones);
+            counts.blink();
+            assert_eq!(counts.total(), Some(stones.0.len() as StoneCount));
+        }
+    }
+
+    #[test]
+    fn equal_stones_counted_together() {
+        let mut counts = StoneCounts::from(&Stones(vec![Stone(0), Stone(0), Stone(1)]));
+        // 1, 1, 2024
+        counts.blink();
+        assert_eq!((counts.total(), counts.distinct()), (Some(3), 2));
+        // 2024, 2024, 20, 24
+        counts.blink();
+        assert_eq!((counts.total(), counts.distinct()), (Some(4), 3));
+    }
+
+    #[test]
+    fn thousands_of_blinks() {
+        let mut counts = StoneCounts::from(&example());
+        counts.blink_times(1000);
+        let settled_at = counts.settled_at().expect("values stop growing");
+        let overflowed_at = counts.overflowed_at().expect("more stones than u128 holds");
+        assert!(settled_at < overflowed_at);
+        assert_eq!(counts.total(), None);
+        let distinct_seen = counts.distinct_seen();
+        counts.blink_times(2000);
+        assert_eq!(counts.blinks(), 3000);
+        assert_eq!(counts.distinct_seen(), distinct_seen);
+        assert!(counts.distinct() <= distinct_seen);
+
+        // the blink before overflowing still fits
+        let mut counts = StoneCounts::from(&example());
+        counts.blink_times(overflowed_at - 1);
+        assert!(counts.total().unwrap() > u64::MAX as StoneCount);
+        // once settled, nothing new shows up
+        let mut counts = StoneCounts::from(&example());
+        counts.blink_times(settled_at - 2);
+        assert!(counts.distinct_seen() < distinct_seen);
+        counts.blink();
+        assert_eq!(counts.distinct_seen(), distinct_seen);
+        assert_eq!(counts.settled_at(), None);
+    }
+}
@@ -12,8 +12,13 @@ use winnow::{
 mod common;
 use crate::common::*;
 
+/// `cargo run --bin p1 -- [input] [--blinks=N]`
 fn main() {
-    util::DayInput::find::<11>().solve_with(solve);
+    let blinks = blinks_from_args();
+    util::day_input!().solve_with(|input| match blinks {
+        Some(blinks) => solve_with_blinks(input, blinks),
+        None => solve(input),
+    });
 }
 
 /// # Problem
@@ -31,53 +36,17 @@ fn main() {
 /// - For other numbers, the number is multiplied by 2024
 ///
 /// The order is preserved. Splitting does not change this.
-fn solve(input: impl AsRef<str>) -> u64 {
+///
+/// Only the amount of stones matters, see [`StoneCounts`].
+fn solve(input: impl AsRef<str>) -> StoneCount {
+    solve_with_blinks(input, 25)
+}
+
+fn solve_with_blinks(input: impl AsRef<str>, blinks: usize) -> StoneCount {
     let initial = parse_stones
         .parse_next(&mut input.as_ref())
         .expect("parsable");
-    after_n_blinks::<25>(initial).0.len() as u64
-}
-
-fn after_n_blinks<const N: u8>(mut stones: Stones) -> Stones {
-    for blinks in 0..N {
-        println!("Blink level: {}", blinks);
-        stones = after_blink(stones);
-    }
-    stones
-}
-
-pub fn after_blink(stones: Stones) -> Stones {
-    Stones(
-        stones
-            .0
-            .into_iter()
-            .flat_map(|stone| {
-                match process(stone) {
-                    (l, Some(r)) => vec![l, r],
-                    (l, None) => vec![l],
-                }
-                .into_iter()
-            })
-            .collect(),
-    )
-}
-
-pub fn process(stone: Stone) -> (Stone, Option<Stone>) {
-    match stone.0 {
-        // num is zero
-        0 => (Stone(1), None),
-        // if amount of digits in number is even
-        n if (n.ilog10() + 1) % 2 == 0 => {
-            let digit_len = n.ilog10() + 1;
-            let half_digit_len = digit_len / 2;
-            let cut_off: u64 = 10_u64.pow(half_digit_len);
-            let left_digits = n / cut_off;
-            let right_digits = n % cut_off;
-            (Stone(left_digits), Some(Stone(right_digits)))
-        }
-        // all other numbers
-        n => (Stone(n * 2024), None),
-    }
+    total_after(&initial, blinks).expect("countable amount of stones")
 }
 
 #[cfg(test)]
@@ -2,8 +2,6 @@
 
 mod common;
 
-use std::collections::HashMap;
-
 use common::*;
 #[allow(unused_imports)]
 use winnow::{
@@ -14,105 +12,58 @@ use winnow::{
     {PResult, Parser},
 };
 
+/// `cargo run --bin p2 -- [input] [--blinks=N] [--stats]`:
+/// `--stats` also shows how many different values the stones had, and when that stopped growing.
 fn main() {
-    util::DayInput::find::<11>().solve_with(solve);
+    let blinks = blinks_from_args().unwrap_or(BLINKS);
+    let input = util::day_input!();
+    let counts = solve_with_blinks(input.as_ref(), blinks);
+    input.solve_with(|_| {
+        counts.total().map_or_else(
+            || format!("more than {}", StoneCount::MAX),
+            |t| t.to_string(),
+        )
+    });
+    if util::has_flag("stats") {
+        eprintln!("After {} blinks:", counts.blinks());
+        eprintln!(" * {} different values now", counts.distinct());
+        eprintln!(" * {} different values so far", counts.distinct_seen());
+        match counts.settled_at() {
+            Some(blink) => eprintln!(" * no new values since blink {}", blink),
+            None => eprintln!(" * still finding new values"),
+        }
+        if let Some(blink) = counts.overflowed_at() {
+            eprintln!(" * too many stones to count since blink {}", blink);
+        }
+    }
 }
 
-fn solve(input: impl AsRef<str>) -> u64 {
-    let initial = parse_stones(&mut input.as_ref()).expect("parsable");
-    len_after_blinks::<75>(initial)
-}
+const BLINKS: usize = 75;
 
-#[derive(Hash, Eq, PartialEq)]
-struct State {
-    stone: Stone,
-    blinks_remain: u8,
+#[cfg(test)]
+fn solve(input: impl AsRef<str>) -> StoneCount {
+    solve_with_blinks(input, BLINKS)
+        .total()
+        .expect("countable amount of stones")
 }
-fn len_after_blinks<const N: u8>(stones: Stones) -> u64 {
-    fn compute_branch(stone: Stone, blinks: u8, visited: &mut HashMap<State, u64>) -> u64 {
-        match stone.0 {
-            0 => len_from_state(
-                State {
-                    stone: Stone(1),
-                    blinks_remain: blinks,
-                },
-                visited,
-            ),
-            // if amount of digits in number is even
-            n if (n.ilog10() + 1) % 2 == 0 => {
-                let digit_len = n.ilog10() + 1;
-                let half_digit_len = digit_len / 2;
-                let cut_off: u64 = 10_u64.pow(half_digit_len);
-                let left_digits = n / cut_off;
-                let right_digits = n % cut_off;
-                len_from_state(
-                    State {
-                        stone: Stone(left_digits),
-                        blinks_remain: blinks,
-                    },
-                    visited,
-                ) + len_from_state(
-                    State {
-                        stone: Stone(right_digits),
-                        blinks_remain: blinks,
-                    },
-                    visited,
-                )
-            }
-            // all other numbers
-            n => len_from_state(
-                State {
-                    stone: Stone(n * 2024),
-                    blinks_remain: blinks,
-                },
-                visited,
-            ),
-        }
-    }
-    fn len_from_state(state: State, visited: &mut HashMap<State, u64>) -> u64 {
-        if let Some(&prev) = visited.get(&state) {
-            // previously visited branch
-            prev
-        } else {
-            // unvisited state
-            let new_result = match state.blinks_remain {
-                // reached leaf for the first time
-                0 => 1,
-                // new branch
-                blinks => compute_branch(state.stone, blinks - 1, visited),
-            };
-            let prev = visited.insert(state, new_result);
-            assert_eq!(prev, None);
-            new_result
-        }
-    }
 
-    let mut visited: HashMap<State, u64> = HashMap::new();
-    stones
-        .0
-        .into_iter()
-        .map(|stone| {
-            len_from_state(
-                State {
-                    stone,
-                    blinks_remain: N,
-                },
-                &mut visited,
-            )
-        })
-        .sum()
+/// Counting every stone one by one takes too long, but stones with the same value
+/// turn into the same stones. Counting stones per value, see [`StoneCounts`],
+/// keeps every blink down to the amount of different values.
+/// Enough blinks make too many stones for even a [`StoneCount`], though.
+fn solve_with_blinks(input: impl AsRef<str>, blinks: usize) -> StoneCounts {
+    let initial = parse_stones(&mut input.as_ref()).expect("parsable");
+    let mut counts = StoneCounts::from(&initial);
+    counts.blink_times(blinks);
+    counts
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::{Stone, Stones};
-
     #[test]
     fn samples() {
-        assert_eq!(
-            super::len_after_blinks::<25>(Stones(vec![Stone(125), Stone(17)])),
-            55312
-        )
+        assert_eq!(super::solve_with_blinks("125 17", 25).total(), Some(55312));
+        assert_eq!(super::solve("125 17"), 65601038650482);
     }
 
     #[test]
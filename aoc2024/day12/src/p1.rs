@@ -5,9 +5,10 @@ use {p1_garden::*, p1_regions::*};
 
 mod p1_garden;
 mod p1_regions;
+mod region_analysis;
 
 fn main() {
-    util::DayInput::find::<12>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
@@ -41,26 +42,8 @@ fn solve(input: impl AsRef<str>) -> u64 {
                 "Calculating value for region:\n{}\n",
                 region.relative_to(&garden)
             );
-            let area = region.positions.len() as u64;
-            let perimeter = region
-                .positions
-                .iter()
-                .map(|pos| {
-                    // amount of edges: `max` - `self-similar neighbors`
-                    let bordering_neighboors = garden
-                        .bordering_pos(pos)
-                        .filter(|bordering_pos| {
-                            garden
-                                .inner
-                                .get(bordering_pos.0)
-                                .is_some_and(|&flower| flower == region.flower)
-                        })
-                        .count();
-                    eprintln!("Position {} has {} neighboors.", pos, bordering_neighboors);
-                    4 - bordering_neighboors
-                })
-                .sum::<usize>() as u64;
-            area * perimeter
+            let analysis = region.relative_to(&garden).analysis();
+            analysis.area * analysis.perimeter
         })
         .sum()
 }
@@ -79,7 +62,6 @@ mod tests {
     fn example_2() {
         assert_eq!(super::solve(include_str!("EXAMPLE_2")), 772);
     }
-    #[ignore]
     #[test]
     fn example_larger() {
         assert_eq!(super::solve(include_str!("EXAMPLE_LARGER")), 1930);
@@ -83,80 +83,29 @@ impl Display for Garden {
     }
 }
 impl Garden {
+    /// Flood fill from every position not in a region yet, through neighbours with the same
+    /// flower. Regions of the same flower are only separate if no such path connects them.
     pub fn get_regions(&self) -> Vec<Region> {
-        /// Recursive exploration of garden map.
-        /// Go every cardinal direction, if the spot is valid and new.
-        /// If nowhere to go, propagate up a collection for the valid flower.
-        /// This flower connects a region, since recursive must be touching.
-        /// If the flower on the upper level is different,
-        /// it means an entire regions' flowers was found.
-        /// This is stored, such that every one of these is unique.
-        /// A vector is used because no key can be used to uniquely identify
-        /// these, except an arbitrary position value.
-        fn explore(
-            garden: &Garden,
-            this_pos: Position,
-            visited: &mut HashSet<Position>,
-        ) -> (Region, Vec<Region>) {
-            eprintln!(
-                "All visited:\n{}\n",
-                garden.as_highlighted(
-                    &Region {
-                        flower: Flower('X'),
-                        positions: visited.clone()
-                    },
-                    Color::Blue
-                )
-            );
-            let this_flower = *garden
-                .inner
-                .get(this_pos.0)
-                .expect("only call this function with a valid position");
-
-            let (bordering_regions, others): (Vec<Region>, Vec<Vec<Region>>) = garden
-                .bordering_pos(&this_pos)
-                .filter(|new_pos| garden.has_pos(new_pos))
-                .sorted_by_key(|valid_pos| {
-                    // sorting by boolean in this way makes self-similar branches go first
-                    garden
-                        .inner
-                        .get(valid_pos.0)
-                        .is_some_and(|&flower| flower != this_flower)
-                })
-                .filter_map(|valid_pos| {
-                    if visited.insert(valid_pos) {
-                        // new position, explore branch
-                        Some(explore(garden, valid_pos, visited))
-                    } else {
-                        // position was already seen
-                        None
-                    }
-                })
-                .unzip();
-
-            bordering_regions.into_iter().fold(
-                (
-                    Region {
-                        flower: this_flower,
-                        positions: HashSet::from([this_pos]),
-                    },
-                    others.into_iter().flatten().collect::<Vec<_>>(),
-                ),
-                |(mut self_similar, mut others), bordering_region| {
-                    if bordering_region.flower == self_similar.flower {
-                        self_similar.positions.extend(bordering_region.positions);
-                    } else {
-                        others.push(bordering_region);
+        let mut visited = HashSet::new();
+        let mut regions = vec![];
+        for (start, &flower) in self.inner.iter().enumerate() {
+            if !visited.insert(Position(start)) {
+                continue;
+            }
+            let mut positions = HashSet::from([Position(start)]);
+            let mut stack = vec![Position(start)];
+            while let Some(pos) = stack.pop() {
+                for next in self.bordering_pos(&pos) {
+                    let same = self.inner.get(next.0).is_some_and(|&f| f == flower);
+                    if same && visited.insert(next) {
+                        positions.insert(next);
+                        stack.push(next);
                     }
-
-                    (self_similar, others)
-                },
-            )
+                }
+            }
+            regions.push(Region { flower, positions });
         }
-
-        let (first, mut rest) = explore(self, Position(0), &mut HashSet::from([Position(0)]));
-        rest.push(first);
-        rest
+        regions
     }
 
     pub fn bordering_pos(&self, this_pos: &Position) -> impl Iterator<Item = Position> {
@@ -183,10 +132,6 @@ impl Garden {
         .map(Position)
     }
 
-    pub fn has_pos(&self, pos: &Position) -> bool {
-        self.inner.len() > pos.0
-    }
-
     #[allow(unused)]
     pub fn as_highlighted<'a>(&'a self, region: &'a Region, color: Color) -> RegionHighlight<'a> {
         RegionHighlight {
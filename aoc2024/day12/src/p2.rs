@@ -1,35 +1,65 @@
 //#![doc = include_str!("../p2.md")]
 
 #[allow(unused_imports)]
-use winnow::{
-    ascii::*,
-    combinator::*,
-    error::*,
-    prelude::*,
-    stream::*,
-    token::*,
-    {PResult, Parser},
-};
+use {p1_garden::*, p1_regions::*};
+
+mod p1_garden;
+mod p1_regions;
+mod region_analysis;
 
 fn main() {
-    util::DayInput::find::<12>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
+/// # Problem
+/// Same regions as part one, but fences are priced by their amount of straight
+/// sides instead of their perimeter. A side may be as long as it needs to be.
+/// # Solution
+/// Every side starts and ends at a corner, so counting corners (both the convex
+/// and the concave ones) of every region gives its amount of sides,
+/// see [`RelativeRegion::analysis`].
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    let garden = input.as_ref().parse::<Garden>().expect("parsable");
+    garden
+        .get_regions()
+        .iter()
+        .map(|region| {
+            let analysis = region.relative_to(&garden).analysis();
+            analysis.area * analysis.sides
+        })
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     #[test]
-    fn trailhead_score_sum_examples() {
-        todo!();
-        assert_eq!(super::solve(include_str!("EXAMPLE")), todo!());
+    fn example_square() {
+        assert_eq!(super::solve(include_str!("SQUARE")), 4 * 4);
+    }
+    #[test]
+    fn example_1() {
+        assert_eq!(super::solve(include_str!("EXAMPLE")), 80);
+    }
+    #[test]
+    fn example_2() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_2")), 436);
+    }
+    #[test]
+    fn example_e() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_E")), 236);
+    }
+    #[test]
+    fn example_diagonal() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_DIAGONAL")), 368);
+    }
+    #[test]
+    fn example_larger() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_LARGER")), 1206);
     }
 
     #[ignore]
     #[test]
     fn input_solvable() {
-        assert_eq!(super::solve(include_str!("../../inputs/10")), 607);
+        assert_eq!(super::solve(include_str!("../../inputs/12")), 0);
     }
 }
@@ -0,0 +1,198 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use itertools::Itertools;
+
+use crate::{p1_garden::Position, p1_regions::RelativeRegion};
+
+/// Everything about the shape of a region, for pricing its fences.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegionAnalysis {
+    pub area: u64,
+    /// Amount of fence segments, one per flower edge not touching the region itself.
+    pub perimeter: u64,
+    /// Amount of straight fences, however long they are.
+    pub sides: u64,
+    /// Amount of enclosed areas of other flowers.
+    pub holes: u64,
+    pub x_span: RangeInclusive<usize>,
+    pub y_span: RangeInclusive<usize>,
+}
+
+impl RelativeRegion<'_> {
+    /// Whether the region has a flower at some (possibly out of bounds) position.
+    fn has(&self, x: isize, y: isize) -> bool {
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return false;
+        };
+        x < self.garden.width
+            && self
+                .region
+                .positions
+                .contains(&Position(y * self.garden.width + x))
+    }
+
+    fn coordinates(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.region.positions.iter().map(|pos| {
+            let (x, y) = (pos.0 % self.garden.width, pos.0 / self.garden.width);
+            (x as isize, y as isize)
+        })
+    }
+
+    pub fn analysis(&self) -> RegionAnalysis {
+        const CARDINAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        let perimeter = self
+            .coordinates()
+            .flat_map(|(x, y)| CARDINAL.map(|(dx, dy)| (x + dx, y + dy)))
+            .filter(|&(x, y)| !self.has(x, y))
+            .count();
+        // every side starts and ends at a corner, so there are as many sides as corners
+        let corners = self
+            .coordinates()
+            .cartesian_product([(-1, -1), (-1, 1), (1, -1), (1, 1)])
+            .filter(|&((x, y), (dx, dy))| {
+                let horizontal = self.has(x + dx, y);
+                let vertical = self.has(x, y + dy);
+                let diagonal = self.has(x + dx, y + dy);
+                // convex, like any corner of a square, or concave, like the inside of an L
+                (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+            })
+            .count();
+        RegionAnalysis {
+            area: self.region.positions.len() as u64,
+            perimeter: perimeter as u64,
+            sides: corners as u64,
+            holes: self.holes(),
+            x_span: self.x_span(),
+            y_span: self.y_span(),
+        }
+    }
+
+    /// Groups of other flowers inside the bounding box which can't reach the outside.
+    /// They can go diagonally, squeezing between two flowers of the region which only touch at
+    /// a corner, since fences around those flowers don't meet.
+    fn holes(&self) -> u64 {
+        let (x_span, y_span) = (self.x_span(), self.y_span());
+        // one extra around the bounding box, which is all connected and outside
+        let xs = *x_span.start() as isize - 1..=*x_span.end() as isize + 1;
+        let ys = *y_span.start() as isize - 1..=*y_span.end() as isize + 1;
+        let mut seen = HashSet::new();
+        let fill = |start: (isize, isize), seen: &mut HashSet<(isize, isize)>| {
+            let mut stack = vec![start];
+            seen.insert(start);
+            while let Some((x, y)) = stack.pop() {
+                for next in (-1..=1)
+                    .cartesian_product(-1..=1)
+                    .map(|(dx, dy)| (x + dx, y + dy))
+                {
+                    let inside = xs.contains(&next.0) && ys.contains(&next.1);
+                    if inside && !self.has(next.0, next.1) && seen.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+        };
+        fill((*xs.start(), *ys.start()), &mut seen);
+        let mut holes = 0;
+        for position in ys
+            .clone()
+            .cartesian_product(xs.clone())
+            .map(|(y, x)| (x, y))
+        {
+            if !self.has(position.0, position.1) && !seen.contains(&position) {
+                fill(position, &mut seen);
+                holes += 1;
+            }
+        }
+        holes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use itertools::Itertools;
+
+    use crate::p1_garden::{Flower, Garden};
+
+    use super::RegionAnalysis;
+
+    fn analyses(input: &str) -> Vec<(Flower, RegionAnalysis)> {
+        let garden = input.parse::<Garden>().unwrap();
+        garden
+            .get_regions()
+            .iter()
+            .map(|r| (r.flower, r.relative_to(&garden).analysis()))
+            .collect()
+    }
+
+    #[test]
+    fn example_shapes() {
+        let by_flower = analyses(include_str!("EXAMPLE"))
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let tests = [
+            ('A', 4, 10, 4, 0..=3, 0..=0),
+            ('B', 4, 8, 4, 0..=1, 1..=2),
+            ('C', 4, 10, 8, 2..=3, 1..=3),
+            ('D', 1, 4, 4, 3..=3, 1..=1),
+            ('E', 3, 8, 4, 0..=2, 3..=3),
+        ];
+        for (flower, area, perimeter, sides, x_span, y_span) in tests {
+            let expected = RegionAnalysis {
+                area,
+                perimeter,
+                sides,
+                holes: 0,
+                x_span,
+                y_span,
+            };
+            assert_eq!(by_flower[&Flower(flower)], expected, "region {}", flower);
+        }
+    }
+
+    #[test]
+    fn holes_counted() {
+        let (o, xs): (Vec<_>, Vec<_>) = analyses(include_str!("EXAMPLE_2"))
+            .into_iter()
+            .partition(|(flower, _)| *flower == Flower('O'));
+        assert_eq!(o.len(), 1);
+        assert_eq!(o[0].1.holes, 4);
+        // every hole has its own four sides
+        assert_eq!(o[0].1.sides, 4 + 4 * 4);
+        assert_eq!(xs.len(), 4);
+        assert!(xs.iter().all(|(_, x)| (x.holes, x.sides) == (0, 4)));
+    }
+
+    #[test]
+    fn diagonal_touches() {
+        let by_flower = analyses(include_str!("EXAMPLE_DIAGONAL"))
+            .into_iter()
+            .into_group_map();
+        let a = &by_flower[&Flower('A')];
+        // both B regions are the same hole, since they touch diagonally
+        assert_eq!((a[0].area, a[0].sides, a[0].holes), (28, 12, 1));
+        assert_eq!(by_flower[&Flower('B')].len(), 2);
+    }
+
+    #[test]
+    fn concave_corners() {
+        let by_flower = analyses(include_str!("EXAMPLE_E"))
+            .into_iter()
+            .into_group_map();
+        let e = &by_flower[&Flower('E')];
+        assert_eq!((e[0].area, e[0].perimeter, e[0].sides), (17, 36, 12));
+    }
+
+    #[test]
+    fn square_shape() {
+        let analyses = analyses(include_str!("SQUARE"));
+        assert_eq!(analyses.len(), 1);
+        let (_, square) = &analyses[0];
+        assert_eq!((square.area, square.perimeter, square.sides), (4, 8, 4));
+        assert_eq!(
+            (square.x_span.clone(), square.y_span.clone()),
+            (0..=1, 0..=1)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+/// Cheapest amount of tokens to win every winnable prize,
+/// with every prize moved `prize_offset` further on both axes.
+pub fn total_cost(input: &str, prize_offset: u64) -> TokenCount {
+    let machines = parse_machines.parse_next(&mut &*input).expect("parsable");
+    machines
+        .iter()
+        .map(|machine| machine.with_prize_offset(prize_offset))
+        .filter_map(|machine| machine.optimal_cost())
+        .sum()
+}
+
+pub fn parse_machines(input: &mut &str) -> PResult<Vec<Machine>> {
+    separated(1.., parse_machine, (line_ending, line_ending)).parse_next(input)
+}
+fn parse_machine(input: &mut &str) -> PResult<Machine> {
+    separated_pair(
+        separated_pair(parse_button_offset, line_ending, parse_button_offset),
+        line_ending,
+        parse_prize_pos,
+    )
+    .map(|((a_offset, b_offset), prize_pos)| Machine::new(a_offset, b_offset, prize_pos))
+    .parse_next(input)
+}
+fn parse_button_offset(input: &mut &str) -> PResult<Vec2> {
+    (
+        preceded(("Button ", alt(('A', 'B')), ": X+"), dec_uint),
+        preceded(", Y+", dec_uint),
+    )
+        .map(|(x, y)| Vec2 { x, y })
+        .parse_next(input)
+}
+fn parse_prize_pos(input: &mut &str) -> PResult<Vec2> {
+    (preceded("Prize: X=", dec_uint), preceded(", Y=", dec_uint))
+        .map(|(x, y)| Vec2 { x, y })
+        .parse_next(input)
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    pub a: Button,
+    pub b: Button,
+    pub prize_pos: Vec2,
+}
+
+impl Machine {
+    pub fn new(a_offset: Vec2, b_offset: Vec2, prize_pos: Vec2) -> Self {
+        Self {
+            a: Button {
+                offset: a_offset,
+                cost: 3,
+            },
+            b: Button {
+                offset: b_offset,
+                cost: 1,
+            },
+            prize_pos,
+        }
+    }
+
+    /// Same machine, with the prize moved on both axes.
+    pub fn with_prize_offset(&self, offset: u64) -> Self {
+        let prize_pos = Vec2 {
+            x: self
+                .prize_pos
+                .x
+                .checked_add(offset)
+                .expect("prize in range"),
+            y: self
+                .prize_pos
+                .y
+                .checked_add(offset)
+                .expect("prize in range"),
+        };
+        Self {
+            prize_pos,
+            ..self.clone()
+        }
+    }
+
+    /// No need to figure out what the actual press sequence was. The token cost
+    /// is all we care about.
+    /// Pressing `A` `a` times and `B` `b` times has to reach the prize on both axes:
+    /// two equations with two unknowns, solved exactly with Cramer's rule.
+    /// If both buttons move in the same direction, there's no single solution,
+    /// and the cheapest one on their shared line is searched instead.
+    /// Products of two `u64` values fit in `u128`, as long as their signs are kept apart.
+    pub fn optimal_cost(&self) -> Option<TokenCount> {
+        let (a, b, prize) = (self.a.offset, self.b.offset, self.prize_pos);
+        let [ax, ay, bx, by, px, py] = [a.x, a.y, b.x, b.y, prize.x, prize.y].map(u128::from);
+        let determinant = (ax * by, ay * bx);
+        let (a_count, b_count) = if determinant.0 != determinant.1 {
+            (
+                exact_quotient((px * by, py * bx), determinant)?,
+                exact_quotient((ax * py, ay * px), determinant)?,
+            )
+        } else {
+            // collinear: one axis decides the counts, unless both buttons go nowhere on it
+            let (x, y, p) = match ax + bx {
+                0 => (ay, by, py),
+                _ => (ax, bx, px),
+            };
+            let costs = (self.a.cost.into(), self.b.cost.into());
+            cheapest_on_line(x, y, p, costs)?
+        };
+        let reach = |a_offset: u128, b_offset: u128| {
+            a_count
+                .checked_mul(a_offset)?
+                .checked_add(b_count.checked_mul(b_offset)?)
+        };
+        if reach(ax, bx) != Some(px) || reach(ay, by) != Some(py) {
+            return None;
+        }
+        let cost = a_count
+            .checked_mul(self.a.cost.into())?
+            .checked_add(b_count.checked_mul(self.b.cost.into())?)?;
+        TokenCount::try_from(cost).ok()
+    }
+}
+
+/// `(n - n_minus) / (d - d_minus)`, if it's a non-negative integer.
+fn exact_quotient((n, n_minus): (u128, u128), (d, d_minus): (u128, u128)) -> Option<u128> {
+    let (numerator, denominator) = (n.abs_diff(n_minus), d.abs_diff(d_minus));
+    let negative = numerator != 0 && (n < n_minus) != (d < d_minus);
+    (!negative && numerator.is_multiple_of(denominator)).then(|| numerator / denominator)
+}
+
+/// Cheapest `a`, `b` with `a * x + b * y = p`.
+fn cheapest_on_line(
+    x: u128,
+    y: u128,
+    p: u128,
+    (a_cost, b_cost): (u128, u128),
+) -> Option<(u128, u128)> {
+    match (x, y) {
+        (0, 0) => (p == 0).then_some((0, 0)),
+        (0, y) => p.is_multiple_of(y).then_some((0, p / y)),
+        (x, 0) => p.is_multiple_of(x).then_some((p / x, 0)),
+        (x, y) => {
+            let (gcd, x_factor, _) = extended_gcd(x as i128, y as i128);
+            let gcd = gcd as u128;
+            if !p.is_multiple_of(gcd) {
+                return None;
+            }
+            // every solution is `(a + k * a_step, b - k * b_step)` for some `k`
+            let (a_step, b_step) = (y / gcd, x / gcd);
+            // smallest `a`, from `a * x ≡ p (mod y)`; both factors are below `a_step`
+            let a = x_factor.rem_euclid(a_step as i128) as u128 * ((p / gcd) % a_step) % a_step;
+            let b = p.checked_sub(a * x)? / y;
+            let steps = match a_step * a_cost < b_step * b_cost {
+                true => b / b_step,
+                false => 0,
+            };
+            Some((a + steps * a_step, b - steps * b_step))
+        }
+    }
+}
+
+/// `(gcd, s, t)` such that `s * a + t * b = gcd`, with `|s|` and `|t|` at most `max(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, s, t) = extended_gcd(b, a % b);
+        (gcd, t, s - (a / b) * t)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Button {
+    pub offset: Vec2,
+    pub cost: TokenCost,
+}
+pub type TokenCost = u8;
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Vec2 {
+    pub x: u64,
+    pub y: u64,
+}
+pub type TokenCount = u64;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine((ax, ay): (u64, u64), (bx, by): (u64, u64), (px, py): (u64, u64)) -> Machine {
+        Machine::new(
+            Vec2 { x: ax, y: ay },
+            Vec2 { x: bx, y: by },
+            Vec2 { x: px, y: py },
+        )
+    }
+
+    #[test]
+    fn example_machines() {
+        let machines = parse_machines
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let costs = machines
+            .iter()
+            .map(Machine::optimal_cost)
+            .collect::<Vec<_>>();
+        assert_eq!(costs, [Some(280), None, Some(200), None]);
+    }
+
+    #[test]
+    fn collinear_buttons_searched() {
+        // A is three times B, at three times the cost: any mix costs the same
+        assert_eq!(machine((3, 6), (1, 2), (10, 20)).optimal_cost(), Some(10));
+        // A is four times B, at three times the cost: as much A as possible
+        assert_eq!(machine((4, 4), (1, 1), (10, 10)).optimal_cost(), Some(8));
+        // A is two times B, at three times the cost: only B
+        assert_eq!(machine((2, 0), (1, 0), (7, 0)).optimal_cost(), Some(7));
+        // needs A, though it isn't cheaper
+        assert_eq!(machine((5, 5), (3, 3), (7, 7)).optimal_cost(), None);
+        assert_eq!(machine((5, 5), (3, 3), (11, 11)).optimal_cost(), Some(5));
+        // on the line of neither button
+        assert_eq!(machine((2, 2), (1, 1), (4, 5)).optimal_cost(), None);
+        // B goes nowhere on the x axis
+        assert_eq!(machine((0, 3), (0, 1), (0, 7)).optimal_cost(), Some(7));
+        assert_eq!(machine((0, 0), (0, 0), (0, 0)).optimal_cost(), Some(0));
+        assert_eq!(machine((0, 0), (0, 0), (1, 0)).optimal_cost(), None);
+    }
+
+    #[test]
+    fn huge_prizes_do_not_overflow() {
+        let far = machine((1, 0), (0, 1), (u64::MAX, u64::MAX));
+        assert_eq!(far.optimal_cost(), None);
+        let far = machine((u64::MAX, 1), (1, u64::MAX), (u64::MAX, u64::MAX));
+        assert_eq!(far.optimal_cost(), None);
+        let skewed = machine(
+            (u64::MAX, u64::MAX - 1),
+            (u64::MAX - 1, u64::MAX),
+            (u64::MAX, u64::MAX - 1),
+        );
+        assert_eq!(skewed.optimal_cost(), Some(3));
+        let collinear = machine((u64::MAX, u64::MAX), (1, 1), (u64::MAX, u64::MAX));
+        assert_eq!(collinear.optimal_cost(), Some(3));
+        let collinear = machine((u64::MAX - 1, 0), (u64::MAX, 0), (u64::MAX, 0));
+        assert_eq!(collinear.optimal_cost(), Some(1));
+    }
+}
@@ -1,5 +1,8 @@
 //#![doc = include_str!("../p2.md")]
 
+mod common;
+
+use common::*;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -11,25 +14,44 @@ use winnow::{
     {PResult, Parser},
 };
 
+/// How much further away every prize actually is, on both axes.
+const PRIZE_OFFSET: u64 = 10_000_000_000_000;
+
 fn main() {
-    util::DayInput::find::<13>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
+/// # Problem
+/// Same as part one, but every prize is `10000000000000` further on both axes.
+/// # Solution
+/// Solving the equations exactly doesn't care how far away the prizes are.
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    total_cost(input.as_ref(), PRIZE_OFFSET)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
-    fn trailhead_score_sum_examples() {
-        todo!();
-        assert_eq!(super::solve(include_str!("EXAMPLE")), todo!());
+    fn only_second_and_fourth_winnable() {
+        let machines = parse_machines
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let winnable = machines
+            .iter()
+            .map(|machine| {
+                machine
+                    .with_prize_offset(PRIZE_OFFSET)
+                    .optimal_cost()
+                    .is_some()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(winnable, [false, true, false, true]);
     }
 
-    #[ignore]
     #[test]
-    fn input_solvable() {
-        assert_eq!(super::solve(include_str!("../../inputs/13")), todo!());
+    fn example() {
+        assert_eq!(super::solve(include_str!("EXAMPLE")), 875318608908);
     }
 }
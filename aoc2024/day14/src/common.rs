@@ -0,0 +1,374 @@
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+pub type Scalar = i16;
+
+/// Size of a space, which robots wrap around.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bounds {
+    pub width: Scalar,
+    pub height: Scalar,
+}
+impl Bounds {
+    pub const STANDARD: Bounds = Bounds {
+        width: 101,
+        height: 103,
+    };
+
+    /// Robots exactly on the middle row or column (only odd sizes have one) are in no
+    /// quadrant. Even sizes split evenly.
+    #[allow(dead_code)] // only p1 counts quadrants
+    pub fn quadrant(&self, pos: Position) -> Quadrant {
+        // which half of an axis, or `None` for the middle
+        let half = |coordinate: Scalar, size: Scalar| match (size % 2, coordinate.cmp(&(size / 2)))
+        {
+            (_, Ordering::Less) => Some(false),
+            (1, Ordering::Equal) => None,
+            _ => Some(true),
+        };
+        match (half(pos.x, self.width), half(pos.y, self.height)) {
+            (Some(false), Some(false)) => Quadrant::TopLeft,
+            (Some(true), Some(false)) => Quadrant::TopRight,
+            (Some(false), Some(true)) => Quadrant::BottomLeft,
+            (Some(true), Some(true)) => Quadrant::BottomRight,
+            (None, _) | (_, None) => Quadrant::MiddleEdge,
+        }
+    }
+
+    /// Seconds until every robot is back where it started, whatever its velocity:
+    /// the cycle of the seconds passed, wrapped around per axis.
+    #[allow(dead_code)] // only p2 searches a whole period
+    pub fn period(&self) -> u64 {
+        let step = |&(x, y): &(Scalar, Scalar)| ((x + 1) % self.width, (y + 1) % self.height);
+        util::cycle::brent((0, 0), step).length as u64
+    }
+}
+impl FromStr for Bounds {
+    type Err = ErrMode<ContextError>;
+
+    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
+        parse_bounds.parse_next(&mut s)
+    }
+}
+
+/// Size given as `--size=W,H`, if any.
+pub fn bounds_from_args() -> Option<Bounds> {
+    util::flag_value("size").map(|size| size.parse().expect("size formatted like `--size=11,7`"))
+}
+
+#[derive(Clone)]
+pub struct Space {
+    pub robots: Vec<Robot>,
+    pub bounds: Bounds,
+}
+impl Space {
+    /// Space of the robots in `input`, sized by `bounds` if given, otherwise by the header line
+    /// of the input (like `size=11,7`) if it has one, otherwise with the standard size.
+    pub fn parse(input: &str, bounds: Option<Bounds>) -> Self {
+        let (header, robots) = parse_input.parse_next(&mut &*input).expect("parsable");
+        Space {
+            robots,
+            bounds: bounds.or(header).unwrap_or(Bounds::STANDARD),
+        }
+    }
+    /// Space after some amount of seconds, moving every robot all at once.
+    /// Positions repeat every `width` (or `height`) seconds, so only the remainder matters.
+    pub fn after(&self, seconds: u64) -> Self {
+        let wrapped = |pos: Scalar, vel: Scalar, size: Scalar| {
+            let seconds = (seconds % size as u64) as i64;
+            (pos as i64 + vel as i64 * seconds).rem_euclid(size as i64) as Scalar
+        };
+        let robots = self
+            .robots
+            .iter()
+            .map(|robot| Robot {
+                pos: Position {
+                    x: wrapped(robot.pos.x, robot.vel.x, self.bounds.width),
+                    y: wrapped(robot.pos.y, robot.vel.y, self.bounds.height),
+                },
+                vel: robot.vel,
+            })
+            .collect();
+        Space {
+            robots,
+            bounds: self.bounds,
+        }
+    }
+    /// Robots in every quadrant, multiplied.
+    /// A quadrant without robots makes it 0.
+    #[allow(dead_code)] // only p1 counts quadrants
+    pub fn safety_factor(&self) -> u64 {
+        eprintln!("Calculating safety:\n{}\n", self.as_quads());
+        let mut counts = [0u64; 4];
+        for robot in &self.robots {
+            match self.bounds.quadrant(robot.pos) {
+                Quadrant::TopLeft => counts[0] += 1,
+                Quadrant::TopRight => counts[1] += 1,
+                Quadrant::BottomLeft => counts[2] += 1,
+                Quadrant::BottomRight => counts[3] += 1,
+                Quadrant::MiddleEdge => {}
+            }
+        }
+        counts.iter().product()
+    }
+    #[allow(unused)]
+    pub fn as_quads(&self) -> QuadSpace<'_> {
+        QuadSpace(self)
+    }
+    #[allow(unused)]
+    pub fn as_robots(&self) -> RobotList<'_> {
+        RobotList(&self.robots)
+    }
+}
+impl Display for Space {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let robots_by_pos = self.robots.iter().map(|r| r.pos).counts();
+        for y in 0..self.bounds.height {
+            for x in 0..self.bounds.width {
+                let pos = Position { x, y };
+                write!(
+                    f,
+                    "{}",
+                    match robots_by_pos.get(&pos) {
+                        None => '.',
+                        Some(&n) => char::from_digit(n as u32, 16).unwrap(),
+                    }
+                )?;
+            }
+            if y != self.bounds.height - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+/// Space without its middle row and column, which are blanked out.
+#[allow(unused)]
+pub struct QuadSpace<'s>(&'s Space);
+impl Display for QuadSpace<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let robots_by_pos = self.0.robots.iter().map(|r| r.pos).counts();
+        let bounds = self.0.bounds;
+        for y in 0..bounds.height {
+            for x in 0..bounds.width {
+                let pos = Position { x, y };
+                match bounds.quadrant(pos) {
+                    Quadrant::MiddleEdge => write!(f, " ")?,
+                    _ => write!(
+                        f,
+                        "{}",
+                        match robots_by_pos.get(&pos) {
+                            None => '.',
+                            Some(&n) => char::from_digit((n as u32).clamp(0, 15), 16).unwrap(),
+                        }
+                    )?,
+                }
+            }
+            if y != bounds.height - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+#[allow(unused)]
+pub struct RobotList<'r>(&'r Vec<Robot>);
+impl Display for RobotList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for robot in self.0 {
+            writeln!(
+                f,
+                "p={},{} v={},{}",
+                robot.pos.x, robot.pos.y, robot.vel.x, robot.vel.y
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// for robots -> group map of robots by key (quadrant variant)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[allow(dead_code)] // only p1 counts quadrants
+pub enum Quadrant {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    // note: middle is ignored for odd width & height
+    MiddleEdge,
+}
+
+#[derive(Clone)]
+pub struct Robot {
+    pub pos: Position,
+    pub vel: Velocity,
+}
+/// Note: standard area known to be 101 wide and 103 tall
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Position {
+    pub x: Scalar,
+    pub y: Scalar,
+}
+/// Note: components seem to be -101 to 101 ish?
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Velocity {
+    pub x: Scalar,
+    pub y: Scalar,
+}
+/// Robots, after an optional header line with the size of their space.
+pub fn parse_input(input: &mut &str) -> PResult<(Option<Bounds>, Vec<Robot>)> {
+    (
+        opt(terminated(preceded("size=", parse_bounds), line_ending)),
+        separated(1.., parse_robot, line_ending),
+    )
+        .parse_next(input)
+}
+fn parse_bounds(input: &mut &str) -> PResult<Bounds> {
+    separated_pair(dec_int, ",", dec_int)
+        .verify(|&(width, height): &(Scalar, Scalar)| width > 0 && height > 0)
+        .map(|(width, height)| Bounds { width, height })
+        .parse_next(input)
+}
+fn parse_robot(input: &mut &str) -> PResult<Robot> {
+    separated_pair(parse_pos, " ", parse_vel)
+        .map(|(pos, vel)| Robot { pos, vel })
+        .parse_next(input)
+}
+fn parse_pos(input: &mut &str) -> PResult<Position> {
+    preceded(
+        "p=",
+        separated_pair(dec_int, ",", dec_int).map(|(x, y)| Position { x, y }),
+    )
+    .parse_next(input)
+}
+fn parse_vel(input: &mut &str) -> PResult<Velocity> {
+    preceded(
+        "v=",
+        separated_pair(dec_int, ",", dec_int).map(|(x, y)| Velocity { x, y }),
+    )
+    .parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::random::XorShift;
+
+    #[test]
+    fn quadrants_mirror_each_other() {
+        let mut random = XorShift::new(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..1_000 {
+            let bounds = Bounds {
+                width: random.below(40) as Scalar + 1,
+                height: random.below(40) as Scalar + 1,
+            };
+            let pos = Position {
+                x: random.below(bounds.width as u64) as Scalar,
+                y: random.below(bounds.height as u64) as Scalar,
+            };
+            let mirrored_x = Position {
+                x: bounds.width - 1 - pos.x,
+                ..pos
+            };
+            let mirrored_y = Position {
+                y: bounds.height - 1 - pos.y,
+                ..pos
+            };
+            let quadrant = bounds.quadrant(pos);
+            let (expected_x, expected_y) = match quadrant {
+                Quadrant::TopLeft => (Quadrant::TopRight, Quadrant::BottomLeft),
+                Quadrant::TopRight => (Quadrant::TopLeft, Quadrant::BottomRight),
+                Quadrant::BottomLeft => (Quadrant::BottomRight, Quadrant::TopLeft),
+                Quadrant::BottomRight => (Quadrant::BottomLeft, Quadrant::TopRight),
+                Quadrant::MiddleEdge => (Quadrant::MiddleEdge, Quadrant::MiddleEdge),
+            };
+            assert_eq!(bounds.quadrant(mirrored_x), expected_x, "{:?}", bounds);
+            assert_eq!(bounds.quadrant(mirrored_y), expected_y, "{:?}", bounds);
+            // only positions which are their own mirror image are in the middle
+            let in_middle = mirrored_x == pos || mirrored_y == pos;
+            assert_eq!(quadrant == Quadrant::MiddleEdge, in_middle, "{:?}", bounds);
+        }
+    }
+
+    #[test]
+    fn periods_are_common_multiples() {
+        assert_eq!(Bounds::STANDARD.period(), 101 * 103);
+        let bounds = Bounds {
+            width: 4,
+            height: 6,
+        };
+        assert_eq!(bounds.period(), 12);
+    }
+
+    #[test]
+    fn quadrants_partition_evenly() {
+        for width in 1..12 {
+            for height in 1..12 {
+                let bounds = Bounds { width, height };
+                let counts = (0..width)
+                    .flat_map(|x| (0..height).map(move |y| Position { x, y }))
+                    .map(|pos| bounds.quadrant(pos))
+                    .counts();
+                let quadrant_size = (width / 2) as usize * (height / 2) as usize;
+                for quadrant in [
+                    Quadrant::TopLeft,
+                    Quadrant::TopRight,
+                    Quadrant::BottomLeft,
+                    Quadrant::BottomRight,
+                ] {
+                    let count = counts.get(&quadrant).copied().unwrap_or_default();
+                    assert_eq!(count, quadrant_size, "{:?} of {:?}", quadrant, bounds);
+                }
+                let middle = counts
+                    .get(&Quadrant::MiddleEdge)
+                    .copied()
+                    .unwrap_or_default();
+                assert_eq!(middle, (width * height) as usize - 4 * quadrant_size);
+            }
+        }
+    }
+
+    #[test]
+    fn sizes_from_header() {
+        let input = format!("size=11,7\n{}", include_str!("EXAMPLE"));
+        let space = Space::parse(&input, None);
+        assert_eq!(
+            space.bounds,
+            Bounds {
+                width: 11,
+                height: 7
+            }
+        );
+        assert_eq!(space.robots.len(), 12);
+        // given sizes go before the header
+        let bounds = Bounds {
+            width: 5,
+            height: 5,
+        };
+        assert_eq!(Space::parse(&input, Some(bounds)).bounds, bounds);
+        assert_eq!(
+            Space::parse(include_str!("EXAMPLE"), None).bounds,
+            Bounds::STANDARD
+        );
+        assert!("0,7".parse::<Bounds>().is_err());
+        assert_eq!("101,103".parse::<Bounds>(), Ok(Bounds::STANDARD));
+    }
+
+    #[test]
+    fn empty_quadrants_are_unsafe() {
+        let space = Space::parse("p=0,0 v=0,0\np=2,0 v=0,0", Some("3,3".parse().unwrap()));
+        assert_eq!(space.safety_factor(), 0);
+    }
+}
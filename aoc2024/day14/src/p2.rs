@@ -1,5 +1,10 @@
 //#![doc = include_str!("../p2.md")]
 
+mod common;
+mod picture;
+
+use common::*;
+use picture::*;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -11,24 +16,36 @@ use winnow::{
     {PResult, Parser},
 };
 
+/// `cargo run --bin p2 -- [input] [--size=W,H] [--show]`:
+/// `--show` tells how the picture was found and draws the robots at that second, to confirm it.
 fn main() {
-    util::DayInput::find::<14>().solve_with(solve);
+    let bounds = bounds_from_args();
+    let input = util::day_input!();
+    let space = Space::parse(input.as_ref(), bounds);
+    let (seconds, detection) = solve(&space);
+    input.solve_with(|_| seconds);
+    if util::has_flag("show") {
+        eprintln!("Found by {:?}.", detection);
+        eprintln!("After {} seconds:\n{}", seconds, space.after(seconds));
+    }
 }
 
-fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+/// # Problem
+/// Most of the robots sometimes arrange themselves into a picture of a
+/// Christmas tree. What is the fewest amount of seconds until they do?
+/// # Solution
+/// Nothing is known about the picture, except that its robots are close
+/// together. See [`find_picture`], which also tells how the picture was found.
+fn solve(space: &Space) -> (u64, Detection) {
+    find_picture(space)
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn example_solvable() {
-        assert_eq!(super::solve(include_str!("EXAMPLE")), todo!());
-    }
-
     #[ignore]
     #[test]
     fn input_solvable() {
-        assert_eq!(super::solve(include_str!("../../inputs/14")), todo!());
+        let space = super::Space::parse(include_str!("../../inputs/14"), None);
+        assert_eq!(super::solve(&space).0, 0);
     }
 }
@@ -0,0 +1,189 @@
+//! Finding the second at which the robots arrange themselves into a picture.
+//!
+//! Nothing says what the picture looks like, only that most robots are part of it.
+//! Robots in a picture are close together, unlike the usual noise spread over the whole space.
+//! Every robot is back where it started after `width` seconds horizontally and after `height`
+//! seconds vertically, so the picture is the only time both axes are unusually bunched up.
+
+use crate::common::*;
+use std::collections::HashSet;
+
+/// How many standard deviations below the average variance of an axis counts as bunched up.
+const DISTINCT_DEVIATIONS: f64 = 5.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Detection {
+    /// Seconds with the least spread per axis, combined by the Chinese remainder theorem.
+    Variance { x_second: u64, y_second: u64 },
+    /// No axis stood out, so every frame was scored by how clustered its robots are.
+    Cluster,
+}
+
+fn variance(values: impl Iterator<Item = Scalar>) -> f64 {
+    let values = values.map(f64::from).collect::<Vec<_>>();
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+/// Second with the lowest variance, if it stands out from all the others.
+fn most_bunched(variances: &[f64]) -> Option<u64> {
+    let count = variances.len() as f64;
+    let mean = variances.iter().sum::<f64>() / count;
+    let deviation = (variances.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count).sqrt();
+    let (second, &lowest) = variances
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    (lowest < mean - DISTINCT_DEVIATIONS * deviation).then_some(second as u64)
+}
+
+/// Smallest second that is `x_second` modulo `width` and `y_second` modulo `height`.
+/// Always exists if both are coprime, like the standard `101` and `103`.
+fn chinese_remainder(x_second: u64, width: u64, y_second: u64, height: u64) -> Option<u64> {
+    (0..height)
+        .map(|k| x_second + k * width)
+        .find(|second| second % height == y_second)
+}
+
+/// Share of occupied positions with another occupied position right next to them.
+/// Robots on the same position count once, since a picture has no need to stack them.
+fn cluster_score(space: &Space) -> f64 {
+    let occupied = space.robots.iter().map(|r| r.pos).collect::<HashSet<_>>();
+    let clustered = occupied
+        .iter()
+        .filter(|pos| {
+            [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .into_iter()
+                .any(|(dx, dy)| {
+                    occupied.contains(&Position {
+                        x: pos.x + dx,
+                        y: pos.y + dy,
+                    })
+                })
+        })
+        .count();
+    clustered as f64 / occupied.len() as f64
+}
+
+/// First second at which the robots form a picture, and how it was found.
+pub fn find_picture(space: &Space) -> (u64, Detection) {
+    let (width, height) = (space.bounds.width as u64, space.bounds.height as u64);
+    let x_variances = (0..width)
+        .map(|second| variance(space.after(second).robots.iter().map(|r| r.pos.x)))
+        .collect::<Vec<_>>();
+    let y_variances = (0..height)
+        .map(|second| variance(space.after(second).robots.iter().map(|r| r.pos.y)))
+        .collect::<Vec<_>>();
+    if let (Some(x_second), Some(y_second)) =
+        (most_bunched(&x_variances), most_bunched(&y_variances))
+    {
+        if let Some(second) = chinese_remainder(x_second, width, y_second, height) {
+            return (second, Detection::Variance { x_second, y_second });
+        }
+    }
+
+    let (_, second) = (0..space.bounds.period())
+        .map(|second| (cluster_score(&space.after(second)), second))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .expect("non-empty period");
+    (second, Detection::Cluster)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::random::XorShift;
+
+    /// Robots at `positions` after `seconds`, moving randomly.
+    fn arranged_at(
+        bounds: Bounds,
+        seconds: u64,
+        positions: impl Iterator<Item = Position>,
+        random: &mut XorShift,
+    ) -> Space {
+        let Bounds { width, height } = bounds;
+        let robots = positions
+            .map(|pos| Robot {
+                pos,
+                vel: Velocity {
+                    x: random.below(2 * width as u64) as Scalar - width,
+                    y: random.below(2 * height as u64) as Scalar - height,
+                },
+            })
+            .collect();
+        // a whole period later, every robot is back where it was
+        let period = bounds.period();
+        Space { robots, bounds }.after(period - seconds % period)
+    }
+
+    #[test]
+    fn bunched_axes_combined() {
+        const SECONDS: u64 = 7_000;
+        let mut random = XorShift::default();
+        let triangle = (0..17).flat_map(|row| {
+            (-row..=row).map(move |column| Position {
+                x: 45 + column,
+                y: 30 + row,
+            })
+        });
+        let noise = (0..200)
+            .map(|_| {
+                (
+                    random.below(Bounds::STANDARD.width as u64) as Scalar,
+                    random.below(Bounds::STANDARD.height as u64) as Scalar,
+                )
+            })
+            .collect::<Vec<_>>();
+        let noise = noise.into_iter().map(|(x, y)| Position { x, y });
+        let space = arranged_at(
+            Bounds::STANDARD,
+            SECONDS,
+            triangle.chain(noise),
+            &mut random,
+        );
+        assert_eq!(
+            find_picture(&space),
+            (
+                SECONDS,
+                Detection::Variance {
+                    x_second: SECONDS % 101,
+                    y_second: SECONDS % 103
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn clusters_without_bunched_axes() {
+        const SECONDS: u64 = 500;
+        let mut random = XorShift::default();
+        // spread over the whole width, so the x axis never stands out
+        let line = (0..31).map(|x| Position { x, y: 20 });
+        let noise = (0..40)
+            .map(|_| (random.below(31) as Scalar, random.below(37) as Scalar))
+            .collect::<Vec<_>>();
+        let noise = noise.into_iter().map(|(x, y)| Position { x, y });
+        let space = arranged_at(
+            Bounds {
+                width: 31,
+                height: 37,
+            },
+            SECONDS,
+            line.chain(noise),
+            &mut random,
+        );
+        assert_eq!(find_picture(&space), (SECONDS, Detection::Cluster));
+    }
+
+    #[test]
+    fn remainders_combined() {
+        assert_eq!(
+            chinese_remainder(7_000 % 101, 101, 7_000 % 103, 103),
+            Some(7_000)
+        );
+        assert_eq!(chinese_remainder(0, 101, 0, 103), Some(0));
+        // not coprime, so not every pair of remainders is possible
+        assert_eq!(chinese_remainder(1, 4, 2, 6), None);
+        assert_eq!(chinese_remainder(1, 4, 3, 6), Some(9));
+    }
+}
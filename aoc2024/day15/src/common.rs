@@ -0,0 +1,425 @@
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+/// Sum of the GPS coordinates of every box, after the robot made every move.
+pub fn gps_sum_after(mut warehouse: Warehouse, moves: &[Direction]) -> u64 {
+    for robot_move in moves {
+        warehouse.process_move(robot_move);
+    }
+    warehouse.gps_sum()
+}
+
+pub fn parse(input: &mut &str) -> PResult<(Warehouse, Vec<Direction>)> {
+    separated_pair(parse_warehouse, (line_ending, line_ending), parse_moves).parse_next(input)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+impl Direction {
+    #[allow(dead_code)] // only replays undo moves
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+/// Moves may be split over multiple lines, which are ignored.
+fn parse_moves(input: &mut &str) -> PResult<Vec<Direction>> {
+    repeat(
+        1..,
+        alt((parse_direction.map(Some), line_ending.value(None))),
+    )
+    .map(|v: Vec<_>| v.into_iter().flatten().collect())
+    .parse_next(input)
+}
+fn parse_direction(input: &mut &str) -> PResult<Direction> {
+    alt((
+        '^'.value(Direction::Up),
+        'v'.value(Direction::Down),
+        '<'.value(Direction::Left),
+        '>'.value(Direction::Right),
+    ))
+    .parse_next(input)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Pos(pub usize);
+impl Pos {
+    /// The map is surrounded by walls, so moving never leaves it.
+    fn moved(&self, width: usize, dir: &Direction) -> Self {
+        Pos(match dir {
+            Direction::Up => self.0 - width,
+            Direction::Down => self.0 + width,
+            Direction::Left => self.0 - 1,
+            Direction::Right => self.0 + 1,
+        })
+    }
+}
+
+/// Map walls (edges) are part of the data, like every other wall.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Warehouse {
+    /// Leftmost position of every box.
+    pub boxes: HashSet<Pos>,
+    pub walls: HashSet<Pos>,
+    pub robot: Pos,
+    pub width: usize,
+    /// Width of every box, `1` in the original warehouse and `2` in the widened one.
+    pub box_width: usize,
+}
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum MapItem {
+    Wall,
+    Box,
+    Robot,
+    Empty,
+}
+
+/// A move of the robot, with everything needed to undo it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub dir: Direction,
+    /// Boxes pushed along (by their position before the move),
+    /// or `None` if something was in the way and nothing moved.
+    pub pushed: Option<Vec<Pos>>,
+}
+
+impl Warehouse {
+    /// Everything except the robot twice as wide, so that boxes are two positions wide.
+    #[allow(dead_code)] // only p2 widens the warehouse
+    pub fn widened(&self) -> Self {
+        let widen =
+            |pos: &Pos| Pos((pos.0 / self.width) * self.width * 2 + (pos.0 % self.width) * 2);
+        Warehouse {
+            boxes: self.boxes.iter().map(widen).collect(),
+            walls: self
+                .walls
+                .iter()
+                .map(widen)
+                .flat_map(|pos| [pos, Pos(pos.0 + 1)])
+                .collect(),
+            robot: widen(&self.robot),
+            width: self.width * 2,
+            box_width: self.box_width * 2,
+        }
+    }
+
+    /// Box taking up some position, by its leftmost position.
+    fn box_at(&self, pos: Pos) -> Option<Pos> {
+        (0..self.box_width)
+            .filter_map(|offset| pos.0.checked_sub(offset).map(Pos))
+            .find(|left| self.boxes.contains(left))
+    }
+
+    /// Moves the robot if nothing blocks it, pushing along every box in the way.
+    /// Boxes push every box they touch in turn, so pushing wide boxes up or down can move a
+    /// whole tree of boxes, and nothing moves at all if any of them is blocked by a wall.
+    pub fn process_move(&mut self, dir: &Direction) -> Step {
+        // in order of being reached, for the step, and as a set, to look up
+        let (mut pushed, mut seen) = (vec![], HashSet::new());
+        let mut frontier = vec![self.robot.moved(self.width, dir)];
+        while let Some(pos) = frontier.pop() {
+            if self.walls.contains(&pos) {
+                return Step {
+                    dir: *dir,
+                    pushed: None,
+                };
+            }
+            if let Some(left) = self.box_at(pos).filter(|&left| seen.insert(left)) {
+                pushed.push(left);
+                frontier.extend(
+                    (0..self.box_width).map(|offset| Pos(left.0 + offset).moved(self.width, dir)),
+                );
+            }
+        }
+        self.shift(&pushed, dir);
+        Step {
+            dir: *dir,
+            pushed: Some(pushed),
+        }
+    }
+
+    /// Moves the robot and some boxes one position, without checking anything.
+    fn shift(&mut self, boxes: &[Pos], dir: &Direction) {
+        for left in boxes {
+            self.boxes.remove(left);
+        }
+        self.boxes
+            .extend(boxes.iter().map(|left| left.moved(self.width, dir)));
+        self.robot = self.robot.moved(self.width, dir);
+    }
+
+    /// Applies a step recorded earlier again.
+    #[allow(dead_code)] // only replays redo steps
+    pub fn redo(&mut self, step: &Step) {
+        if let Some(pushed) = &step.pushed {
+            self.shift(pushed, &step.dir);
+        }
+    }
+
+    /// Reverts a step, assuming it was the last one applied.
+    #[allow(dead_code)] // only replays undo steps
+    pub fn undo(&mut self, step: &Step) {
+        if let Some(pushed) = &step.pushed {
+            let moved = pushed
+                .iter()
+                .map(|left| left.moved(self.width, &step.dir))
+                .collect_vec();
+            self.shift(&moved, &step.dir.opposite());
+        }
+    }
+
+    /// `100` times the distance from the top plus the distance from the left,
+    /// of the leftmost position of every box.
+    pub fn gps_sum(&self) -> u64 {
+        self.boxes
+            .iter()
+            .map(|pos| 100 * (pos.0 / self.width) as u64 + (pos.0 % self.width) as u64)
+            .sum()
+    }
+}
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let height = self
+            .walls
+            .iter()
+            .map(|pos| pos.0 / self.width)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        for y in 0..height {
+            for x in 0..self.width {
+                let pos = Pos(y * self.width + x);
+                let c = if self.walls.contains(&pos) {
+                    '#'
+                } else if self.robot == pos {
+                    '@'
+                } else {
+                    match (self.box_at(pos), self.box_width) {
+                        (None, _) => '.',
+                        (Some(_), 1) => 'O',
+                        (Some(left), _) if left == pos => '[',
+                        (Some(_), _) => ']',
+                    }
+                };
+                write!(f, "{}", c)?;
+            }
+            if y != height - 1 {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A whole run of the robot, which can be stepped through in both directions.
+#[allow(dead_code)] // only p2 replays runs
+pub struct Replay {
+    warehouse: Warehouse,
+    steps: Vec<Step>,
+    /// Amount of steps applied to `warehouse`.
+    applied: usize,
+}
+impl Replay {
+    /// Makes every move, ending up after the last one.
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn record(mut warehouse: Warehouse, moves: &[Direction]) -> Self {
+        let steps = moves
+            .iter()
+            .map(|dir| warehouse.process_move(dir))
+            .collect_vec();
+        Replay {
+            warehouse,
+            applied: steps.len(),
+            steps,
+        }
+    }
+
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn warehouse(&self) -> &Warehouse {
+        &self.warehouse
+    }
+
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn applied(&self) -> usize {
+        self.applied
+    }
+
+    /// Undoes the last applied step, if any.
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn back(&mut self) -> Option<&Step> {
+        self.applied = self.applied.checked_sub(1)?;
+        let step = &self.steps[self.applied];
+        self.warehouse.undo(step);
+        Some(step)
+    }
+
+    /// Applies the next step, if any.
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn forward(&mut self) -> Option<&Step> {
+        let step = self.steps.get(self.applied)?;
+        self.warehouse.redo(step);
+        self.applied += 1;
+        Some(step)
+    }
+
+    /// Steps back or forward until `applied` steps are applied.
+    #[allow(dead_code)] // only p2 replays runs
+    pub fn seek(&mut self, applied: usize) {
+        while self.applied > applied && self.back().is_some() {}
+        while self.applied < applied && self.forward().is_some() {}
+    }
+}
+
+fn parse_warehouse(input: &mut &str) -> PResult<Warehouse> {
+    separated(1.., parse_line, line_ending)
+        .verify(|v: &Vec<Vec<MapItem>>| v.iter().map(|v| v.len()).all_equal())
+        .verify_map(|v: Vec<Vec<MapItem>>| {
+            let width = v[0].len();
+            let mut warehouse = Warehouse {
+                boxes: HashSet::new(),
+                walls: HashSet::new(),
+                robot: Pos(0),
+                width,
+                box_width: 1,
+            };
+            let mut robots = vec![];
+            for (pos, item) in v.into_iter().flatten().enumerate() {
+                match item {
+                    MapItem::Wall => {
+                        warehouse.walls.insert(Pos(pos));
+                    }
+                    MapItem::Box => {
+                        warehouse.boxes.insert(Pos(pos));
+                    }
+                    MapItem::Robot => robots.push(Pos(pos)),
+                    MapItem::Empty => {}
+                }
+            }
+            let robot = robots.into_iter().exactly_one().ok()?;
+            Some(Warehouse { robot, ..warehouse })
+        })
+        .parse_next(input)
+}
+fn parse_line(input: &mut &str) -> PResult<Vec<MapItem>> {
+    repeat(1.., parse_item).parse_next(input)
+}
+fn parse_item(input: &mut &str) -> PResult<MapItem> {
+    alt((
+        '#'.value(MapItem::Wall),
+        'O'.value(MapItem::Box),
+        '@'.value(MapItem::Robot),
+        '.'.value(MapItem::Empty),
+    ))
+    .parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_pushes_move_nothing() {
+        let (warehouse, _) = parse.parse_next(&mut include_str!("EXAMPLE")).unwrap();
+        let mut warehouse = warehouse;
+        // wall right next to the robot
+        let before = warehouse.clone();
+        assert_eq!(warehouse.process_move(&Direction::Left).pushed, None);
+        assert_eq!(warehouse, before);
+    }
+
+    #[test]
+    fn wide_pushes_cascade() {
+        let (warehouse, _) = parse.parse_next(&mut include_str!("EXAMPLE_WIDE")).unwrap();
+        let mut warehouse = warehouse.widened();
+        assert_eq!(
+            warehouse.to_string(),
+            "##############\n\
+             ##......##..##\n\
+             ##..........##\n\
+             ##....[][]@.##\n\
+             ##....[]....##\n\
+             ##..........##\n\
+             ##############"
+        );
+        let (_, moves) = parse.parse_next(&mut include_str!("EXAMPLE_WIDE")).unwrap();
+        // `<vv<<` goes below the lower box
+        for dir in &moves[..5] {
+            warehouse.process_move(dir);
+        }
+        // the lower box pushes both upper boxes
+        let step = warehouse.process_move(&moves[5]);
+        assert_eq!(step.pushed.map(|pushed| pushed.len()), Some(3));
+        let pushed = warehouse.to_string();
+        assert_eq!(
+            pushed,
+            "##############\n\
+             ##......##..##\n\
+             ##...[][]...##\n\
+             ##....[]....##\n\
+             ##.....@....##\n\
+             ##..........##\n\
+             ##############"
+        );
+        // one of the upper boxes is blocked, so none of them move
+        let step = warehouse.process_move(&moves[6]);
+        assert_eq!(step.pushed, None);
+        assert_eq!(warehouse.to_string(), pushed);
+        for dir in &moves[7..] {
+            warehouse.process_move(dir);
+        }
+        assert_eq!(
+            warehouse.to_string(),
+            "##############\n\
+             ##...[].##..##\n\
+             ##...@.[]...##\n\
+             ##....[]....##\n\
+             ##..........##\n\
+             ##..........##\n\
+             ##############"
+        );
+    }
+
+    #[test]
+    fn replays_both_ways() {
+        let (warehouse, moves) = parse
+            .parse_next(&mut include_str!("EXAMPLE_LARGER"))
+            .unwrap();
+        let start = warehouse.widened();
+        let mut replay = Replay::record(start.clone(), &moves);
+        let end = replay.warehouse().clone();
+        replay.seek(0);
+        assert_eq!(replay.warehouse(), &start);
+        assert_eq!(replay.back(), None);
+        // stepping through again ends up at the same place as simulating
+        let mut simulated = start.clone();
+        for dir in &moves[..100] {
+            simulated.process_move(dir);
+        }
+        replay.seek(100);
+        assert_eq!(replay.warehouse(), &simulated);
+        replay.seek(moves.len());
+        assert_eq!(replay.warehouse(), &end);
+        assert_eq!(replay.forward(), None);
+        assert_eq!(replay.applied(), moves.len());
+    }
+}
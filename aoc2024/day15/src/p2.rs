@@ -1,5 +1,8 @@
 //#![doc = include_str!("../p2.md")]
 
+mod common;
+
+use common::*;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -11,24 +14,63 @@ use winnow::{
     {PResult, Parser},
 };
 
+/// `cargo run --bin p2 -- [input] [--replay[=forward]]`:
+/// `--replay` draws the warehouse after every move, from the last one back to the start,
+/// or from the start up to the last one with `--replay=forward`.
 fn main() {
-    util::DayInput::find::<15>().solve_with(solve);
+    let input = util::day_input!();
+    input.solve_with(solve);
+    if util::has_flag("replay") {
+        let forward = util::flag_value("replay").is_some_and(|way| way == "forward");
+        let (warehouse, moves) = parse.parse_next(&mut input.as_ref()).expect("parsable");
+        let mut replay = Replay::record(warehouse.widened(), &moves);
+        if forward {
+            replay.seek(0);
+        }
+        loop {
+            eprintln!(
+                "After {} moves:\n{}\n",
+                replay.applied(),
+                replay.warehouse()
+            );
+            let step = if forward {
+                replay.forward()
+            } else {
+                replay.back()
+            };
+            if step.is_none() {
+                break;
+            }
+        }
+    }
 }
 
+/// # Problem
+/// Same as part one, but everything except the robot is twice as wide.
+/// Boxes (`[]`) now take up two positions, and can push two other boxes at once.
+/// # Solution
+/// Pushing collects every box touched by the ones pushed so far, and only moves
+/// them if none of them touches a wall. See [`Warehouse::process_move`].
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    let (warehouse, moves) = parse.parse_next(&mut input.as_ref()).expect("parsable");
+    gps_sum_after(warehouse.widened(), &moves)
 }
 
 #[cfg(test)]
 mod tests {
     #[test]
     fn example_solvable() {
-        assert_eq!(super::solve(include_str!("EXAMPLE")), todo!());
+        assert_eq!(super::solve(include_str!("EXAMPLE_WIDE")), 105 + 207 + 306);
+    }
+
+    #[test]
+    fn larger_example_solvable() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_LARGER")), 9021);
     }
 
     #[ignore]
     #[test]
     fn input_solvable() {
-        assert_eq!(super::solve(include_str!("../../inputs/15")), todo!());
+        assert_eq!(super::solve(include_str!("../../inputs/15")), 0);
     }
 }
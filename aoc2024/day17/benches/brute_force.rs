@@ -0,0 +1,51 @@
+//! Brute force search for the first quine of `EXAMPLE_P2`, interpreted versus compiled.
+// benches are built with `--cfg test`, pulling in the unit tests of the modules below
+#![allow(dead_code)]
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+use winnow::Parser;
+
+#[path = "../src/common.rs"]
+mod common;
+#[path = "../src/compiled.rs"]
+mod compiled;
+
+use common::*;
+use compiled::*;
+
+const FIRST_QUINE: ArbitraryUInt = 117440;
+
+fn brute_force(c: &mut Criterion) {
+    let program = parse_computer
+        .parse_next(&mut include_str!("../src/EXAMPLE_P2"))
+        .expect("parsable")
+        .program;
+    let compiled = CompiledProgram::compile(&program);
+
+    let mut group = c.benchmark_group("day17 brute force quine");
+    group.sample_size(10);
+    group.bench_function("interpreted", |b| {
+        b.iter(|| {
+            (0..=FIRST_QUINE).find(|&a| {
+                let mut computer = Computer {
+                    a: Register(a),
+                    program: program.clone(),
+                    ..Default::default()
+                };
+                while computer.step() {}
+                computer.output.0 == program.0
+            })
+        })
+    });
+    group.bench_function("compiled", |b| {
+        b.iter(|| (0..=FIRST_QUINE).find(|&a| compiled.output_for(black_box(a)) == program.0))
+    });
+    group.bench_function("compiled, stopping at first wrong output", |b| {
+        b.iter(|| (0..=FIRST_QUINE).find(|&a| compiled.outputs(black_box(a), &program.0)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, brute_force);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+//! Disassembles the program of a puzzle input, or assembles source back into a program.
+//!
+//! `cargo run --bin asm -- [file]`: inputs starting with `Register` are disassembled,
+//! anything else is treated as assembly source (see `assembly`).
+
+mod assembly;
+mod common;
+
+use assembly::*;
+use common::*;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+fn main() {
+    let input = util::day_input!();
+    if input.as_ref().starts_with("Register") {
+        let computer = parse_computer
+            .parse_next(&mut input.as_ref())
+            .expect("parsable");
+        println!("{}", disassemble(&computer.program));
+    } else {
+        match assemble(input.as_ref()) {
+            Ok(program) => println!("Program: {}", program),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
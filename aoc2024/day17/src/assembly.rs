@@ -0,0 +1,315 @@
+//! Disassembler and assembler for [`OpCode`]s.
+//!
+//! The disassembly has one instruction per line, with combo operands decoded to `a`/`b`/`c`,
+//! and jump targets replaced by labels. Every line is commented with what it does:
+//! ```text
+//! L0:
+//!     adv 1          ; A = A >> 1
+//!     out a          ; output A % 8
+//!     jnz L0         ; if A != 0 goto L0
+//! ```
+//! Assembling that source gives back `0,1,5,4,3,0`.
+//! Jumps to odd or out-of-program positions keep their number, since they cannot have a label.
+//! `bxc` ignores its operand, so it is only written out when it isn't `0`.
+
+use crate::common::*;
+use anyhow::{anyhow, bail};
+use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap};
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+fn label(position: usize) -> String {
+    format!("L{}", position)
+}
+
+fn combo_name(operand: ThreeBitValue) -> String {
+    match ComboOperand::from(operand) {
+        ComboOperand::Literal(n) => u8::from(n).to_string(),
+        ComboOperand::RegA => "a".to_string(),
+        ComboOperand::RegB => "b".to_string(),
+        ComboOperand::RegC => "c".to_string(),
+        ComboOperand::Reserved => "reserved".to_string(),
+    }
+}
+
+/// Readable pseudo-assembly, see module documentation.
+pub fn disassemble(program: &Program) -> String {
+    let instructions = program.0.chunks(2).collect_vec();
+    // only even positions inside the program start an instruction we print
+    let labelled: BTreeSet<usize> = instructions
+        .iter()
+        .filter_map(|pair| match pair {
+            [opcode, target] if OpCode::from(*opcode) == OpCode::Jnz => {
+                Some(u8::from(*target) as usize)
+            }
+            _ => None,
+        })
+        .filter(|&target| target % 2 == 0 && target + 1 < program.0.len())
+        .collect();
+
+    let mut lines = Vec::new();
+    for (index, pair) in instructions.into_iter().enumerate() {
+        let position = index * 2;
+        if labelled.contains(&position) {
+            lines.push(format!("{}:", label(position)));
+        }
+        let &[opcode, operand] = pair else {
+            lines.push(format!(
+                "    ; trailing value {} is never run",
+                u8::from(pair[0])
+            ));
+            continue;
+        };
+        let opcode = OpCode::from(opcode);
+        let literal = u8::from(operand);
+        let combo = combo_name(operand);
+        // registers are uppercase in the explanation, like in the puzzle text
+        let value = combo.to_uppercase();
+        let (operand, meaning) = match opcode {
+            OpCode::Adv => (combo.clone(), format!("A = A >> {}", value)),
+            OpCode::Bdv => (combo.clone(), format!("B = A >> {}", value)),
+            OpCode::Cdv => (combo.clone(), format!("C = A >> {}", value)),
+            OpCode::Bxl => (literal.to_string(), format!("B = B ^ {}", literal)),
+            OpCode::Bst => (combo.clone(), format!("B = {} % 8", value)),
+            OpCode::Jnz => {
+                let target = literal as usize;
+                let target = if labelled.contains(&target) {
+                    label(target)
+                } else {
+                    target.to_string()
+                };
+                (target.clone(), format!("if A != 0 goto {}", target))
+            }
+            OpCode::Bxc if literal == 0 => (String::new(), "B = B ^ C".to_string()),
+            OpCode::Bxc => (literal.to_string(), "B = B ^ C".to_string()),
+            OpCode::Out => (combo.clone(), format!("output {} % 8", value)),
+        };
+        let instruction = format!("{} {}", opcode.mnemonic(), operand);
+        lines.push(format!("    {:<14} ; {}", instruction.trim_end(), meaning));
+    }
+    lines.join("\n")
+}
+
+/// A single line of assembly source.
+enum Line<'s> {
+    Label(&'s str),
+    Instruction(OpCode, Option<Operand<'s>>),
+}
+enum Operand<'s> {
+    Value(u8),
+    Register(ThreeBitValue),
+    Label(&'s str),
+}
+
+/// Assemble source (like the output of [`disassemble`]) back into a program.
+pub fn assemble(source: &str) -> anyhow::Result<Program> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .filter_map(|(line_number, line)| {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            (!line.is_empty()).then(|| {
+                parse_line
+                    .parse(line)
+                    .map(|parsed| (line_number + 1, parsed))
+                    .map_err(|e| {
+                        anyhow!("line {}: cannot parse {:?}\n{}", line_number + 1, line, e)
+                    })
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // first pass: every label points at the next instruction
+    let mut labels = HashMap::new();
+    let mut position = 0;
+    for (line_number, line) in &lines {
+        match line {
+            Line::Label(name) => {
+                if labels.insert(*name, position).is_some() {
+                    bail!("line {}: label {} defined twice", line_number, name);
+                }
+            }
+            Line::Instruction(..) => position += 2,
+        }
+    }
+
+    // second pass: encode
+    let mut program = Vec::new();
+    for (line_number, line) in lines {
+        let Line::Instruction(opcode, operand) = line else {
+            continue;
+        };
+        let operand = match (opcode, operand) {
+            (OpCode::Bxc, None) => 0,
+            (_, None) => bail!(
+                "line {}: {} needs an operand",
+                line_number,
+                opcode.mnemonic()
+            ),
+            (OpCode::Jnz, Some(Operand::Label(name))) => *labels
+                .get(name)
+                .ok_or_else(|| anyhow!("line {}: unknown label {}", line_number, name))?,
+            (_, Some(Operand::Label(name))) => {
+                bail!("line {}: only jnz can jump to {}", line_number, name)
+            }
+            (
+                OpCode::Adv | OpCode::Bdv | OpCode::Cdv | OpCode::Bst | OpCode::Out,
+                Some(operand),
+            ) => match operand {
+                Operand::Value(n @ 0..=3) => n,
+                Operand::Value(n) => bail!(
+                    "line {}: combo operand {} must be a register",
+                    line_number,
+                    n
+                ),
+                Operand::Register(register) => u8::from(register),
+                Operand::Label(_) => unreachable!("handled above"),
+            },
+            (OpCode::Bxl | OpCode::Jnz | OpCode::Bxc, Some(Operand::Value(n))) => n,
+            (_, Some(Operand::Register(_))) => bail!(
+                "line {}: {} takes a literal operand",
+                line_number,
+                opcode.mnemonic()
+            ),
+        };
+        let operand = ThreeBitValue::try_from(operand).map_err(|_| {
+            anyhow!(
+                "line {}: operand {} needs more than 3 bits",
+                line_number,
+                operand
+            )
+        })?;
+        program.push(ThreeBitValue::try_from(opcode as u8).expect("opcodes are 3 bits"));
+        program.push(operand);
+    }
+    Ok(Program(program))
+}
+
+fn parse_line<'s>(input: &mut &'s str) -> PResult<Line<'s>> {
+    alt((
+        terminated(parse_label_name, ':').map(Line::Label),
+        (parse_opcode, opt(preceded(space1, parse_operand)))
+            .map(|(opcode, operand)| Line::Instruction(opcode, operand)),
+    ))
+    .parse_next(input)
+}
+
+fn parse_label_name<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    (
+        one_of(|c: char| c.is_ascii_alphabetic() || c == '_'),
+        take_while(0.., |c: char| c.is_ascii_alphanumeric() || c == '_'),
+    )
+        .take()
+        .parse_next(input)
+}
+
+fn parse_operand<'s>(input: &mut &'s str) -> PResult<Operand<'s>> {
+    alt((
+        dec_uint.map(Operand::Value),
+        terminated(
+            alt((
+                "a".value(ThreeBitValue::Four),
+                "b".value(ThreeBitValue::Five),
+                "c".value(ThreeBitValue::Six),
+                "reserved".value(ThreeBitValue::Seven),
+            )),
+            eof,
+        )
+        .map(Operand::Register),
+        parse_label_name.map(Operand::Label),
+    ))
+    .parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program_of(mut input: &str) -> Program {
+        parse_computer
+            .parse_next(&mut input)
+            .expect("parsable")
+            .program
+    }
+
+    fn program(values: &[u8]) -> Program {
+        Program(
+            values
+                .iter()
+                .map(|&v| v.try_into().expect("3 bit value"))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn example_disassembles() {
+        assert_eq!(
+            disassemble(&program_of(include_str!("EXAMPLE"))),
+            "L0:
+    adv 1          ; A = A >> 1
+    out a          ; output A % 8
+    jnz L0         ; if A != 0 goto L0"
+        );
+    }
+
+    #[test]
+    fn examples_round_trip() {
+        for input in [include_str!("EXAMPLE"), include_str!("EXAMPLE_P2")] {
+            let program = program_of(input);
+            let source = disassemble(&program);
+            let assembled = assemble(&source).expect("disassembly is valid source");
+            assert_eq!(assembled, program, "source was:\n{}", source);
+            assert!(input.trim_end().ends_with(&assembled.to_string()));
+        }
+    }
+
+    #[test]
+    fn odd_jumps_and_ignored_operands_round_trip() {
+        let program = program(&[2, 4, 3, 1, 4, 5, 1, 7, 5, 5, 3, 0]);
+        let source = disassemble(&program);
+        assert!(
+            source.contains("jnz 1 "),
+            "odd jumps have no label:\n{}",
+            source
+        );
+        assert!(
+            source.contains("bxc 5 "),
+            "ignored operand is kept:\n{}",
+            source
+        );
+        assert_eq!(assemble(&source).expect("valid source"), program);
+    }
+
+    #[test]
+    fn handwritten_source_assembles() {
+        let source = "
+            ; prints every octal digit of A, lowest first
+            loop:
+                out a
+                adv 3
+                jnz loop
+        ";
+        assert_eq!(
+            assemble(source).expect("valid source").to_string(),
+            "5,4,0,3,3,0"
+        );
+    }
+
+    #[test]
+    fn invalid_source_is_rejected() {
+        assert!(assemble("adv 4").is_err(), "combo 4 is written as `a`");
+        assert!(assemble("bxl a").is_err(), "bxl takes a literal");
+        assert!(assemble("jnz nowhere").is_err(), "label must exist");
+        assert!(assemble("bxl 8").is_err(), "operand is 3 bits");
+        assert!(assemble("x:\nx:\nout a").is_err(), "labels are unique");
+    }
+}
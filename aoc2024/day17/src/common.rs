@@ -0,0 +1,324 @@
+use itertools::Itertools;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::fmt::{Display, Formatter};
+use std::ops::BitXorAssign;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+#[derive(Debug, IntoPrimitive, TryFromPrimitive, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[repr(u8)]
+pub enum ThreeBitValue {
+    Zero = 0,
+    One = 1,
+    Two = 2,
+    Three = 3,
+    Four = 4,
+    Five = 5,
+    Six = 6,
+    Seven = 7,
+}
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct LiteralOperand(pub ThreeBitValue);
+impl From<LiteralOperand> for ArbitraryUInt {
+    fn from(value: LiteralOperand) -> Self {
+        u8::from(value.0).into()
+    }
+}
+
+#[derive(IntoPrimitive, TryFromPrimitive, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[repr(u8)]
+pub enum TwoBitValue {
+    Zero = 0,
+    One = 1,
+    Two = 2,
+    Three = 3,
+}
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum ComboOperand {
+    /// 0..=3: Literal values
+    Literal(TwoBitValue),
+    /// 4: Value of register `A`
+    RegA,
+    /// 5: Value of register `B`
+    RegB,
+    /// 6: Value of register `C`
+    RegC,
+    /// 7: "reserved and does not appear in valid programs" (can be ignored?)
+    Reserved,
+}
+impl From<ThreeBitValue> for ComboOperand {
+    fn from(value: ThreeBitValue) -> Self {
+        match value {
+            n @ (ThreeBitValue::Zero
+            | ThreeBitValue::One
+            | ThreeBitValue::Two
+            | ThreeBitValue::Three) => Self::Literal(
+                u8::from(n)
+                    .try_into()
+                    .expect("0..=3 fits inside TwoBitValue"),
+            ),
+            ThreeBitValue::Four => Self::RegA,
+            ThreeBitValue::Five => Self::RegB,
+            ThreeBitValue::Six => Self::RegC,
+            ThreeBitValue::Seven => Self::Reserved,
+        }
+    }
+}
+
+/// # OpCode values
+/// All arithmetic operations, as well as XOR, all have "closure", especially XOR,
+/// because for all intents and purposes the outputs of these can be stored in arbitrary registers.
+///
+/// In order, 0..=7.
+#[repr(u8)]
+#[derive(Debug, TryFromPrimitive, Copy, Clone, Eq, PartialEq)]
+pub enum OpCode {
+    /// division with register `A` and a combo operand `O` : `A / 2.pow(O) -> A`
+    Adv = 0,
+    /// bitwise XOR of `B` and a literal operand `L`: `B^L -> B`
+    Bxl = 1,
+    /// combo operand `O` modulo `8` (keeping lowest 3 bits): `O mod 8 -> B`
+    Bst = 2,
+    /// noop if `A==0`, else set ins_ptr `I` (jump) to literal `L`: `if A==0 { L -> I }`
+    /// if this happens, `I` is not incremented.
+    /// jumps can be odd, meaning that `ins_ptr` isn't necessarily an even number
+    Jnz = 3,
+    /// bitwise XOR of `B` and `C` stored to `B` (still reads operand): `B^C -> B`
+    Bxc = 4,
+    /// combo `O` modulus 8, printed: `print(O mod 8)`
+    Out = 5,
+    /// division like `Adv` but written to `B` (src is still `A`): `A / 2.pow(O) -> B`
+    Bdv = 6,
+    /// division like `Adv` but written to `C` (src is still `A`): `A / 2.pow(O) -> C`
+    Cdv = 7,
+}
+impl OpCode {
+    /// Lowercase assembly name, e.g. `adv`.
+    #[allow(dead_code)] // only the debugger and assembler print instructions
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            OpCode::Adv => "adv",
+            OpCode::Bxl => "bxl",
+            OpCode::Bst => "bst",
+            OpCode::Jnz => "jnz",
+            OpCode::Bxc => "bxc",
+            OpCode::Out => "out",
+            OpCode::Bdv => "bdv",
+            OpCode::Cdv => "cdv",
+        }
+    }
+}
+impl From<ThreeBitValue> for OpCode {
+    fn from(value: ThreeBitValue) -> Self {
+        Into::<u8>::into(value)
+            .try_into()
+            .expect("same variant count")
+    }
+}
+pub type ArbitraryUInt = usize;
+/// `numerator / 2.pow(exponent)`, as a shift, which is zero once every bit is shifted out
+/// (`2.pow(exponent)` itself would overflow).
+#[allow(dead_code)] // the assembler doesn't run programs
+pub fn divide_by_power_of_two(numerator: ArbitraryUInt, exponent: ArbitraryUInt) -> ArbitraryUInt {
+    u32::try_from(exponent)
+        .ok()
+        .and_then(|exponent| numerator.checked_shr(exponent))
+        .unwrap_or(0)
+}
+#[derive(Default)]
+pub struct Register(pub ArbitraryUInt);
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Program(pub Vec<ThreeBitValue>);
+/// Comma-separated, like in the input.
+impl Display for Program {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().map(|&v| u8::from(v)).join(","))
+    }
+}
+#[derive(Default)]
+pub struct ProgramOutput(pub Vec<ThreeBitValue>);
+impl Display for ProgramOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().map(|&v| u8::from(v)).join(","))
+    }
+}
+#[derive(Default)]
+pub struct InstructionPtr(pub ArbitraryUInt);
+impl InstructionPtr {
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub fn increment(&mut self) {
+        self.0 += 2;
+    }
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub fn set(&mut self, literal: LiteralOperand) {
+        self.0 = literal.0 as ArbitraryUInt
+    }
+}
+#[derive(Default)]
+pub struct Computer {
+    pub a: Register,
+    pub b: Register,
+    pub c: Register,
+    pub program: Program,
+    pub ins_ptr: InstructionPtr,
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub output: ProgramOutput,
+}
+impl Display for Computer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // print instruction pointer above program
+        writeln!(
+            f,
+            "         {}v-- IPTR={}",
+            "  ".repeat(self.ins_ptr.0),
+            self.ins_ptr.0
+        )?;
+        // print program
+        writeln!(f, "Program: {}", self.program)?;
+        // print state of registers
+        writeln!(f, "Register A: {}", self.a.0)?;
+        writeln!(f, "Register B: {}", self.b.0)?;
+        write!(f, "Register C: {}", self.c.0)
+    }
+}
+impl Computer {
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub fn calc_combo(&self, operand: ThreeBitValue) -> ArbitraryUInt {
+        match ComboOperand::from(operand) {
+            ComboOperand::Literal(n) => u8::from(n) as ArbitraryUInt,
+            ComboOperand::RegA => self.a.0,
+            ComboOperand::RegB => self.b.0,
+            ComboOperand::RegC => self.c.0,
+            ComboOperand::Reserved => unreachable!("is not supposed to appear for valid programs"),
+        }
+    }
+    #[allow(dead_code)] // only p1 runs programs to the end
+    pub fn run(mut self) -> Self {
+        eprintln!("Starting program with state:\n{}\n", self);
+        while self.step() {
+            eprintln!("{}", self);
+        }
+        self
+    }
+
+    /// Execute the next instruction, or return `false` if the program has halted.
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub fn step(&mut self) -> bool {
+        let Some((opcode, operand_value)) = self.next_instruction() else {
+            return false;
+        };
+        // not in order, but grouped in relation
+        match opcode {
+            // conditional jump
+            OpCode::Jnz if self.a.0 != 0 => self.ins_ptr.set(LiteralOperand(operand_value)),
+            // all other branches
+            non_jmp @ (OpCode::Jnz
+            | OpCode::Adv
+            | OpCode::Bdv
+            | OpCode::Cdv
+            | OpCode::Bxl
+            | OpCode::Bxc
+            | OpCode::Bst
+            | OpCode::Out) => {
+                self.ins_ptr.increment();
+                match non_jmp {
+                    OpCode::Jnz => { /* do nothing */ }
+                    // division
+                    OpCode::Adv => {
+                        self.a.0 = divide_by_power_of_two(self.a.0, self.calc_combo(operand_value))
+                    }
+                    OpCode::Bdv => {
+                        self.b.0 = divide_by_power_of_two(self.a.0, self.calc_combo(operand_value))
+                    }
+                    OpCode::Cdv => {
+                        self.c.0 = divide_by_power_of_two(self.a.0, self.calc_combo(operand_value))
+                    }
+                    // bitwise XOR
+                    OpCode::Bxl => self.b.0 ^= usize::from(LiteralOperand(operand_value)),
+                    OpCode::Bxc => self.b.0.bitxor_assign(self.c.0),
+                    // modulo 8
+                    OpCode::Bst => self.b.0 = self.calc_combo(operand_value) % 8,
+                    OpCode::Out => self.output.0.push(
+                        u8::try_from(self.calc_combo(operand_value) % 8usize)
+                            .ok()
+                            .and_then(|v| v.try_into().ok())
+                            .expect("v % 8 < 8"),
+                    ),
+                };
+            }
+        }
+        true
+    }
+
+    #[allow(dead_code)] // the assembler doesn't run programs
+    pub fn next_instruction(&self) -> Option<(OpCode, ThreeBitValue)> {
+        self.program.0.get(self.ins_ptr.0).and_then(|&x| {
+            self.program
+                .0
+                .get(self.ins_ptr.0 + 1)
+                .map(|&y| (OpCode::from(x), y))
+        })
+    }
+}
+
+pub fn parse_computer(input: &mut &str) -> PResult<Computer> {
+    separated_pair(
+        (
+            terminated(parse_register, line_ending),
+            terminated(parse_register, line_ending),
+            terminated(parse_register, line_ending),
+        ),
+        line_ending,
+        parse_program,
+    )
+    .map(|((a, b, c), program)| Computer {
+        a,
+        b,
+        c,
+        program,
+        ins_ptr: InstructionPtr(0),
+        output: ProgramOutput(Vec::new()),
+    })
+    .parse_next(input)
+}
+pub fn parse_register(input: &mut &str) -> PResult<Register> {
+    preceded(
+        ("Register ", alpha1, ": "),
+        dec_uint.map(|n: ArbitraryUInt| Register(n)),
+    )
+    .parse_next(input)
+}
+pub fn parse_program(input: &mut &str) -> PResult<Program> {
+    preceded(
+        "Program: ",
+        separated(
+            0..,
+            dec_uint.try_map(|n: u8| ThreeBitValue::try_from(n)),
+            ',',
+        ),
+    )
+    .map(|p: Vec<ThreeBitValue>| Program(p))
+    .parse_next(input)
+}
+#[allow(dead_code)] // only the debugger and assembler parse instructions
+pub fn parse_opcode(input: &mut &str) -> PResult<OpCode> {
+    alt((
+        "adv".value(OpCode::Adv),
+        "bxl".value(OpCode::Bxl),
+        "bst".value(OpCode::Bst),
+        "jnz".value(OpCode::Jnz),
+        "bxc".value(OpCode::Bxc),
+        "out".value(OpCode::Out),
+        "bdv".value(OpCode::Bdv),
+        "cdv".value(OpCode::Cdv),
+    ))
+    .parse_next(input)
+}
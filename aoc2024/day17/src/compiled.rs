@@ -0,0 +1,319 @@
+//! Programs compiled ahead of time, for running the same program many times (brute force over A).
+//!
+//! [`Computer::step`] decodes the opcode and operand of an instruction every time it runs it.
+//! Compiling decodes every position once instead, resolving combo operands to a register or a constant,
+//! and folding constants into the instruction: `bst 3` becomes `B = 3`, `adv 2` a plain shift,
+//! and `adv 0` does nothing.
+use crate::common::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Reg {
+    A,
+    B,
+    C,
+}
+
+/// A combo operand, resolved once.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Value {
+    Const(ArbitraryUInt),
+    Reg(Reg),
+    /// Only fails if it is actually run, like in [`Computer::calc_combo`].
+    Reserved,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Op {
+    /// `adv 0`
+    Nop,
+    /// `dest = A >> bits`, for a constant amount of at most 3 bits
+    Shift { dest: Reg, bits: u32 },
+    /// `dest = A >> amount`
+    ShiftBy { dest: Reg, amount: Value },
+    /// `B = B ^ n`
+    XorB(ArbitraryUInt),
+    /// `B = value % 8` (combo constants are below 8 already)
+    SetB(Value),
+    /// `if A != 0 goto target`
+    Jump(usize),
+    /// `B = B ^ C`
+    XorBC,
+    /// `output value % 8`
+    Out(Value),
+    /// `output n`
+    OutConst(ThreeBitValue),
+}
+
+/// Registers and instruction pointer of a running compiled program.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Execution {
+    pub a: ArbitraryUInt,
+    pub b: ArbitraryUInt,
+    pub c: ArbitraryUInt,
+    pub ins_ptr: usize,
+    pub output: Vec<ThreeBitValue>,
+}
+impl Execution {
+    fn read(&self, value: Value) -> ArbitraryUInt {
+        match value {
+            Value::Const(n) => n,
+            Value::Reg(Reg::A) => self.a,
+            Value::Reg(Reg::B) => self.b,
+            Value::Reg(Reg::C) => self.c,
+            Value::Reserved => unreachable!("is not supposed to appear for valid programs"),
+        }
+    }
+
+    fn set(&mut self, register: Reg, value: ArbitraryUInt) {
+        match register {
+            Reg::A => self.a = value,
+            Reg::B => self.b = value,
+            Reg::C => self.c = value,
+        }
+    }
+}
+
+fn three_bits(n: ArbitraryUInt) -> ThreeBitValue {
+    ((n % 8) as u8).try_into().expect("n % 8 < 8")
+}
+
+pub struct CompiledProgram {
+    /// Instruction starting at every position, since jumps may land on odd positions.
+    /// The last position has no operand, so it halts like running past the end.
+    ops: Vec<Op>,
+}
+impl CompiledProgram {
+    pub fn compile(program: &Program) -> Self {
+        let ops = program
+            .0
+            .windows(2)
+            .map(|pair| {
+                let literal = u8::from(pair[1]) as ArbitraryUInt;
+                let combo = match ComboOperand::from(pair[1]) {
+                    ComboOperand::Literal(n) => Value::Const(u8::from(n) as ArbitraryUInt),
+                    ComboOperand::RegA => Value::Reg(Reg::A),
+                    ComboOperand::RegB => Value::Reg(Reg::B),
+                    ComboOperand::RegC => Value::Reg(Reg::C),
+                    ComboOperand::Reserved => Value::Reserved,
+                };
+                let shift = |dest| match combo {
+                    Value::Const(bits) => Op::Shift {
+                        dest,
+                        bits: bits as u32,
+                    },
+                    amount => Op::ShiftBy { dest, amount },
+                };
+                match OpCode::from(pair[0]) {
+                    OpCode::Adv if combo == Value::Const(0) => Op::Nop,
+                    OpCode::Adv => shift(Reg::A),
+                    OpCode::Bdv => shift(Reg::B),
+                    OpCode::Cdv => shift(Reg::C),
+                    OpCode::Bxl => Op::XorB(literal),
+                    OpCode::Bst => Op::SetB(combo),
+                    OpCode::Jnz => Op::Jump(literal),
+                    OpCode::Bxc => Op::XorBC,
+                    OpCode::Out => match combo {
+                        Value::Const(n) => Op::OutConst(three_bits(n)),
+                        value => Op::Out(value),
+                    },
+                }
+            })
+            .collect();
+        Self { ops }
+    }
+
+    /// Execute the next instruction, or return `false` if the program has halted.
+    pub fn step(&self, execution: &mut Execution) -> bool {
+        let Some(&op) = self.ops.get(execution.ins_ptr) else {
+            return false;
+        };
+        execution.ins_ptr += 2;
+        match op {
+            Op::Nop => {}
+            Op::Shift { dest, bits } => execution.set(dest, execution.a >> bits),
+            Op::ShiftBy { dest, amount } => {
+                let shifted = divide_by_power_of_two(execution.a, execution.read(amount));
+                execution.set(dest, shifted)
+            }
+            Op::XorB(n) => execution.b ^= n,
+            Op::SetB(value) => execution.b = execution.read(value) % 8,
+            Op::Jump(target) => {
+                if execution.a != 0 {
+                    execution.ins_ptr = target
+                }
+            }
+            Op::XorBC => execution.b ^= execution.c,
+            Op::Out(value) => execution.output.push(three_bits(execution.read(value))),
+            Op::OutConst(n) => execution.output.push(n),
+        }
+        true
+    }
+
+    /// Output of the whole program for some initial value of A (B and C start at zero).
+    /// Only the tests and benchmark need all of it, p2 stops at the first wrong output.
+    #[allow(dead_code)]
+    pub fn output_for(&self, a: ArbitraryUInt) -> Vec<ThreeBitValue> {
+        let mut execution = Execution {
+            a,
+            ..Default::default()
+        };
+        while self.step(&mut execution) {}
+        execution.output
+    }
+
+    /// Whether the output for some initial value of A is exactly `expected`.
+    /// Stops as soon as the output differs, which is what makes brute force fast.
+    pub fn outputs(&self, a: ArbitraryUInt, expected: &[ThreeBitValue]) -> bool {
+        let mut execution = Execution {
+            a,
+            ..Default::default()
+        };
+        while self.step(&mut execution) {
+            let printed = execution.output.len();
+            if printed > 0 && execution.output.get(printed - 1) != expected.get(printed - 1) {
+                return false;
+            }
+        }
+        execution.output.len() == expected.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::Parser;
+
+    /// Whether the interpreter would hit a reserved combo operand on its next step.
+    fn runs_reserved(computer: &Computer) -> bool {
+        computer
+            .next_instruction()
+            .is_some_and(|(opcode, operand)| {
+                matches!(
+                    opcode,
+                    OpCode::Adv | OpCode::Bdv | OpCode::Cdv | OpCode::Bst | OpCode::Out
+                ) && operand == ThreeBitValue::Seven
+            })
+    }
+
+    /// Run interpreter and compiled program in lockstep, comparing their whole state after every step.
+    fn assert_same_steps(computer: Computer, max_steps: usize) {
+        let compiled = CompiledProgram::compile(&computer.program);
+        let mut computer = computer;
+        let mut execution = Execution {
+            a: computer.a.0,
+            b: computer.b.0,
+            c: computer.c.0,
+            ..Default::default()
+        };
+        for _ in 0..max_steps {
+            if runs_reserved(&computer) {
+                return;
+            }
+            let running = computer.step();
+            assert_eq!(compiled.step(&mut execution), running, "{}", computer);
+            assert_eq!(
+                (
+                    execution.a,
+                    execution.b,
+                    execution.c,
+                    execution.ins_ptr,
+                    &execution.output
+                ),
+                (
+                    computer.a.0,
+                    computer.b.0,
+                    computer.c.0,
+                    computer.ins_ptr.0,
+                    &computer.output.0
+                ),
+                "{}",
+                computer
+            );
+            if !running {
+                return;
+            }
+        }
+    }
+
+    fn program_of(mut input: &str) -> Program {
+        parse_computer
+            .parse_next(&mut input)
+            .expect("parsable")
+            .program
+    }
+
+    #[test]
+    fn constants_fold() {
+        let program = Program(
+            [0, 0, 2, 3, 5, 6, 5, 1]
+                .into_iter()
+                .map(|v: u8| v.try_into().expect("3 bit value"))
+                .collect(),
+        );
+        let compiled = CompiledProgram::compile(&program);
+        assert_eq!(compiled.ops[0], Op::Nop);
+        assert_eq!(compiled.ops[2], Op::SetB(Value::Const(3)));
+        assert_eq!(compiled.ops[4], Op::Out(Value::Reg(Reg::C)));
+        assert_eq!(compiled.ops[6], Op::OutConst(ThreeBitValue::One));
+    }
+
+    #[test]
+    fn examples_match_interpreter() {
+        for input in [include_str!("EXAMPLE"), include_str!("EXAMPLE_P2")] {
+            let program = program_of(input);
+            for a in 0..2048 {
+                assert_same_steps(
+                    Computer {
+                        a: Register(a),
+                        program: program.clone(),
+                        ..Default::default()
+                    },
+                    1000,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_programs_match_interpreter() {
+        let mut rng = util::random::XorShift::new(0x5eed_1234_abcd_0017);
+        for _ in 0..5000 {
+            let len = 2 + rng.below(15) as usize;
+            let program = Program(
+                (0..len)
+                    .map(|_| (rng.below(8) as u8).try_into().expect("3 bit value"))
+                    .collect(),
+            );
+            // mix of small values and values with every bit in use
+            let mut register = || match rng.below(3) {
+                0 => rng.below(64) as ArbitraryUInt,
+                _ => rng.next_u64() as ArbitraryUInt,
+            };
+            assert_same_steps(
+                Computer {
+                    a: Register(register()),
+                    b: Register(register()),
+                    c: Register(register()),
+                    program,
+                    ..Default::default()
+                },
+                200,
+            );
+        }
+    }
+
+    #[test]
+    fn early_exit_agrees_with_full_output() {
+        let program = program_of(include_str!("EXAMPLE_P2"));
+        let compiled = CompiledProgram::compile(&program);
+        for a in 0..=117440 {
+            assert_eq!(
+                compiled.outputs(a, &program.0),
+                compiled.output_for(a) == program.0,
+                "A = {}",
+                a
+            );
+        }
+        assert!(compiled.outputs(117440, &program.0));
+    }
+}
@@ -0,0 +1,59 @@
+//! Interactive step debugger for the 3-bit computer.
+//!
+//! `cargo run --bin debug -- [input] [command file]` reads commands from the command file,
+//! or line by line from stdin if none is given. See `debugger` for the available commands.
+
+mod common;
+mod debugger;
+
+use common::*;
+use debugger::*;
+use std::io::BufRead;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+fn main() {
+    let input = util::day_input!();
+    let computer = parse_computer
+        .parse_next(&mut input.as_ref())
+        .expect("parsable");
+    let mut debugger = Debugger::new(computer);
+    println!("{}\n", debugger.computer());
+
+    if let Some(path) = std::env::args().nth(2) {
+        let script = std::fs::read_to_string(path).expect("command file needs to exist");
+        for stop in debugger.run_script(&script).expect("valid command file") {
+            println!("({})", stop);
+        }
+        print_state(&debugger);
+        return;
+    }
+
+    for line in std::io::stdin().lock().lines() {
+        match parse_line(&line.expect("readable stdin")) {
+            Ok(Some(command)) => match debugger.execute(command) {
+                Some(stop) => {
+                    println!("({})", stop);
+                    print_state(&debugger);
+                }
+                None if command == Command::Print => print_state(&debugger),
+                None => {}
+            },
+            Ok(None) => {}
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn print_state(debugger: &Debugger) {
+    println!("{}", debugger.computer());
+    println!("Output: {}\n", debugger.computer().output);
+}
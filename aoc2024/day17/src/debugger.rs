@@ -0,0 +1,422 @@
+//! Step debugger on top of [`Computer`].
+//!
+//! Every command can be given on its own line, so a whole debugging session can be
+//! written down in a command file and replayed (see `DEBUG_SCRIPT`).
+//!
+//! | Command                      | Effect                                                        |
+//! |------------------------------|---------------------------------------------------------------|
+//! | `step [n]`, `s [n]`          | execute up to `n` (default 1) instructions                    |
+//! | `reverse [n]`, `rs [n]`      | undo up to `n` (default 1) instructions                       |
+//! | `continue`, `c`              | execute until a breakpoint, a watchpoint, or the program halts |
+//! | `break ip <n>`               | stop before executing the instruction at position `n`          |
+//! | `break op <mnemonic>`        | stop before executing any instruction with that opcode         |
+//! | `watch <a/b/c>`              | stop after an instruction changes that register                |
+//! | `clear`                      | remove every breakpoint and watchpoint                         |
+//! | `print`, `p`                 | show the computer                                             |
+//!
+//! `step` also stops early for breakpoints and watchpoints. Breakpoints are checked before every
+//! instruction, except the one execution last stopped before. Anything after `#` is a comment.
+
+use crate::common::*;
+use anyhow::anyhow;
+use std::fmt::{Display, Formatter};
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RegisterName {
+    A,
+    B,
+    C,
+}
+impl RegisterName {
+    fn read(self, computer: &Computer) -> ArbitraryUInt {
+        match self {
+            RegisterName::A => computer.a.0,
+            RegisterName::B => computer.b.0,
+            RegisterName::C => computer.c.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Breakpoint {
+    /// Stop before executing the instruction at this position.
+    InstructionPtr(ArbitraryUInt),
+    /// Stop before executing any instruction with this opcode.
+    OpCode(OpCode),
+}
+impl Breakpoint {
+    fn hit_by(self, computer: &Computer) -> bool {
+        match self {
+            Breakpoint::InstructionPtr(position) => computer.ins_ptr.0 == position,
+            Breakpoint::OpCode(opcode) => computer
+                .next_instruction()
+                .is_some_and(|(next, _)| next == opcode),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Command {
+    Step(usize),
+    ReverseStep(usize),
+    Continue,
+    Break(Breakpoint),
+    Watch(RegisterName),
+    Clear,
+    Print,
+}
+
+/// Why execution stopped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Stop {
+    /// Every requested step was taken (or undone).
+    Stepped,
+    /// The next instruction has a breakpoint.
+    Breakpoint(Breakpoint),
+    /// The last instruction changed a watched register.
+    Watchpoint {
+        register: RegisterName,
+        old: ArbitraryUInt,
+        new: ArbitraryUInt,
+    },
+    /// The instruction pointer is past the end of the program.
+    Halted,
+    /// There is no history left to undo.
+    AtStart,
+}
+impl Display for Stop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stop::Stepped => write!(f, "stepped"),
+            Stop::Breakpoint(Breakpoint::InstructionPtr(position)) => {
+                write!(f, "breakpoint at IPTR={}", position)
+            }
+            Stop::Breakpoint(Breakpoint::OpCode(opcode)) => {
+                write!(f, "breakpoint on {}", opcode.mnemonic())
+            }
+            Stop::Watchpoint { register, old, new } => {
+                write!(
+                    f,
+                    "watchpoint: register {:?} changed {} -> {}",
+                    register, old, new
+                )
+            }
+            Stop::Halted => write!(f, "halted"),
+            Stop::AtStart => write!(f, "at start of history"),
+        }
+    }
+}
+
+/// Everything an instruction can change, so it can be undone.
+/// The program never changes, and output is only ever appended to.
+#[derive(Copy, Clone)]
+struct Snapshot {
+    a: ArbitraryUInt,
+    b: ArbitraryUInt,
+    c: ArbitraryUInt,
+    ins_ptr: ArbitraryUInt,
+    output_len: usize,
+}
+
+pub struct Debugger {
+    computer: Computer,
+    history: Vec<Snapshot>,
+    breakpoints: Vec<Breakpoint>,
+    watchpoints: Vec<RegisterName>,
+    /// Execution stopped before the next instruction for a breakpoint, so resuming executes it.
+    at_breakpoint: bool,
+}
+impl Debugger {
+    pub fn new(computer: Computer) -> Self {
+        Self {
+            computer,
+            history: Vec::new(),
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            at_breakpoint: false,
+        }
+    }
+
+    pub fn computer(&self) -> &Computer {
+        &self.computer
+    }
+
+    pub fn execute(&mut self, command: Command) -> Option<Stop> {
+        match command {
+            Command::Step(count) => Some(self.step(count)),
+            Command::ReverseStep(count) => Some(self.reverse_step(count)),
+            Command::Continue => Some(self.resume()),
+            Command::Break(breakpoint) => {
+                self.breakpoints.push(breakpoint);
+                None
+            }
+            Command::Watch(register) => {
+                self.watchpoints.push(register);
+                None
+            }
+            Command::Clear => {
+                self.breakpoints.clear();
+                self.watchpoints.clear();
+                None
+            }
+            Command::Print => None,
+        }
+    }
+
+    /// Execute every command in a command file, collecting where execution stopped.
+    pub fn run_script(&mut self, script: &str) -> anyhow::Result<Vec<Stop>> {
+        let mut stops = Vec::new();
+        for (line_number, line) in script.lines().enumerate() {
+            let command =
+                parse_line(line).map_err(|e| anyhow!("line {}: {}", line_number + 1, e))?;
+            if let Some(stop) = command.and_then(|command| self.execute(command)) {
+                stops.push(stop);
+            }
+        }
+        Ok(stops)
+    }
+
+    pub fn step(&mut self, count: usize) -> Stop {
+        for _ in 0..count {
+            if let Some(stop) = self.execute_one() {
+                return stop;
+            }
+        }
+        Stop::Stepped
+    }
+
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            if let Some(stop) = self.execute_one() {
+                return stop;
+            }
+        }
+    }
+
+    pub fn reverse_step(&mut self, count: usize) -> Stop {
+        self.at_breakpoint = false;
+        for _ in 0..count {
+            let Some(snapshot) = self.history.pop() else {
+                return Stop::AtStart;
+            };
+            self.restore(snapshot);
+        }
+        Stop::Stepped
+    }
+
+    /// Execute a single instruction, and tell whether to stop after it,
+    /// or stop before it for a breakpoint.
+    fn execute_one(&mut self) -> Option<Stop> {
+        if !std::mem::take(&mut self.at_breakpoint) {
+            if let Some(&breakpoint) = self
+                .breakpoints
+                .iter()
+                .find(|breakpoint| breakpoint.hit_by(&self.computer))
+            {
+                self.at_breakpoint = true;
+                return Some(Stop::Breakpoint(breakpoint));
+            }
+        }
+        let before = self.snapshot();
+        if !self.computer.step() {
+            return Some(Stop::Halted);
+        }
+        self.history.push(before);
+
+        let changed_watchpoint = self.watchpoints.iter().find_map(|&register| {
+            let old = match register {
+                RegisterName::A => before.a,
+                RegisterName::B => before.b,
+                RegisterName::C => before.c,
+            };
+            let new = register.read(&self.computer);
+            (old != new).then_some(Stop::Watchpoint { register, old, new })
+        });
+        changed_watchpoint.or_else(|| {
+            self.computer
+                .next_instruction()
+                .is_none()
+                .then_some(Stop::Halted)
+        })
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            a: self.computer.a.0,
+            b: self.computer.b.0,
+            c: self.computer.c.0,
+            ins_ptr: self.computer.ins_ptr.0,
+            output_len: self.computer.output.0.len(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.computer.a.0 = snapshot.a;
+        self.computer.b.0 = snapshot.b;
+        self.computer.c.0 = snapshot.c;
+        self.computer.ins_ptr.0 = snapshot.ins_ptr;
+        self.computer.output.0.truncate(snapshot.output_len);
+    }
+}
+
+/// Parse a single line of a command file, which may be empty or only a comment.
+pub fn parse_line(line: &str) -> anyhow::Result<Option<Command>> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    parse_command
+        .parse(line)
+        .map(Some)
+        .map_err(|e| anyhow!("unknown command {:?}\n{}", line, e))
+}
+
+fn parse_command(input: &mut &str) -> PResult<Command> {
+    let count = || opt(preceded(space1, dec_uint)).map(|n: Option<usize>| n.unwrap_or(1));
+    alt((
+        preceded(alt(("step", "s")), count()).map(Command::Step),
+        preceded(alt(("reverse", "rs")), count()).map(Command::ReverseStep),
+        alt(("continue", "clear", "c")).map(|word| match word {
+            "clear" => Command::Clear,
+            _ => Command::Continue,
+        }),
+        preceded(("break", space1), parse_breakpoint).map(Command::Break),
+        preceded(("watch", space1), parse_register_name).map(Command::Watch),
+        alt(("print", "p")).value(Command::Print),
+    ))
+    .parse_next(input)
+}
+
+fn parse_breakpoint(input: &mut &str) -> PResult<Breakpoint> {
+    alt((
+        preceded(("ip", space1), dec_uint).map(Breakpoint::InstructionPtr),
+        preceded(("op", space1), parse_opcode).map(Breakpoint::OpCode),
+    ))
+    .parse_next(input)
+}
+
+fn parse_register_name(input: &mut &str) -> PResult<RegisterName> {
+    alt((
+        one_of(['a', 'A']).value(RegisterName::A),
+        one_of(['b', 'B']).value(RegisterName::B),
+        one_of(['c', 'C']).value(RegisterName::C),
+    ))
+    .parse_next(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_debugger() -> Debugger {
+        Debugger::new(
+            parse_computer
+                .parse_next(&mut include_str!("EXAMPLE"))
+                .expect("parsable"),
+        )
+    }
+
+    #[test]
+    fn commands_parse() {
+        assert_eq!(parse_line("s").unwrap(), Some(Command::Step(1)));
+        assert_eq!(parse_line("step 3").unwrap(), Some(Command::Step(3)));
+        assert_eq!(parse_line("rs 2").unwrap(), Some(Command::ReverseStep(2)));
+        assert_eq!(parse_line("c").unwrap(), Some(Command::Continue));
+        assert_eq!(parse_line("clear").unwrap(), Some(Command::Clear));
+        assert_eq!(
+            parse_line("break op out # before printing").unwrap(),
+            Some(Command::Break(Breakpoint::OpCode(OpCode::Out)))
+        );
+        assert_eq!(
+            parse_line("break ip 4").unwrap(),
+            Some(Command::Break(Breakpoint::InstructionPtr(4)))
+        );
+        assert_eq!(
+            parse_line("watch a").unwrap(),
+            Some(Command::Watch(RegisterName::A))
+        );
+        assert_eq!(parse_line("   # only a comment").unwrap(), None);
+        assert!(parse_line("jump 3").is_err());
+    }
+
+    #[test]
+    fn continue_runs_like_computer() {
+        let mut debugger = example_debugger();
+        assert_eq!(debugger.resume(), Stop::Halted);
+        assert_eq!(
+            debugger.computer().output.to_string(),
+            "4,6,3,5,6,3,5,2,1,0"
+        );
+        assert_eq!(debugger.step(1), Stop::Halted, "stays halted");
+    }
+
+    #[test]
+    fn reverse_step_undoes_everything() {
+        let mut debugger = example_debugger();
+        debugger.resume();
+        assert_eq!(debugger.reverse_step(usize::MAX), Stop::AtStart);
+        assert_eq!(debugger.computer().a.0, 729);
+        assert_eq!(debugger.computer().ins_ptr.0, 0);
+        assert!(debugger.computer().output.0.is_empty());
+    }
+
+    #[test]
+    fn breakpoints_stop_before_instruction() {
+        let mut debugger = example_debugger();
+        debugger.execute(Command::Break(Breakpoint::InstructionPtr(0)));
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::InstructionPtr(0)),
+            "before the very first instruction"
+        );
+        assert_eq!(debugger.computer().a.0, 729);
+        assert_eq!(
+            debugger.step(1),
+            Stop::Stepped,
+            "steps over where it stopped"
+        );
+        assert_eq!(debugger.computer().a.0, 364);
+        assert_eq!(
+            debugger.resume(),
+            Stop::Breakpoint(Breakpoint::InstructionPtr(0)),
+            "after jumping back"
+        );
+        assert_eq!(debugger.computer().a.0, 364);
+        assert_eq!(debugger.computer().output.to_string(), "4");
+    }
+
+    #[test]
+    fn script_drives_debugger() {
+        let mut debugger = example_debugger();
+        let stops = debugger
+            .run_script(include_str!("DEBUG_SCRIPT"))
+            .expect("valid script");
+        assert_eq!(
+            stops,
+            vec![
+                Stop::Watchpoint {
+                    register: RegisterName::A,
+                    old: 729,
+                    new: 364
+                },
+                Stop::Breakpoint(Breakpoint::OpCode(OpCode::Out)),
+                Stop::Breakpoint(Breakpoint::InstructionPtr(4)),
+                Stop::Stepped,
+                Stop::Stepped,
+                Stop::Halted,
+                Stop::AtStart,
+            ]
+        );
+        assert_eq!(debugger.computer().a.0, 729, "back at the very start");
+    }
+}
@@ -1,5 +1,11 @@
 //#![doc = include_str!("../p2.md")]
 
+mod common;
+mod compiled;
+
+use common::*;
+use compiled::*;
+use std::fmt::{Display, Formatter};
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -12,29 +18,255 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<13>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
 /// The lowest possible initial value for register A,
 /// that makes the program output itself (its source program)?
 /// # Approach
-/// This must have something to do with optimization.
-/// Brute forcing probably wouldn't work.
-/// I can maybe analyze my input for specific patterns that can be optimized,
-/// even if it doesn't generalize to other solutions?
-/// I should start by doing the naive solution for the example though.
+/// Brute forcing doesn't work, but the programs all have the same shape:
+/// a loop that outputs a single value, shifts A right by 3 bits, and stops once A is zero.
+/// The last output therefore only depends on the highest 3 bits of A,
+/// the second to last on the highest 6 bits, and so on.
+/// We work backwards from the last output, extending every candidate by 3 bits at a time,
+/// and only keep the candidates whose (partial) run outputs the end of the program.
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    let computer = parse_computer
+        .parse_next(&mut input.as_ref())
+        .expect("parsable");
+    find_quine(&computer.program).unwrap_or_else(|e| panic!("{}", e)) as u64
+}
+
+/// Why a program cannot be searched backwards 3 bits at a time.
+#[derive(Debug, Eq, PartialEq)]
+enum UnsupportedProgram {
+    /// The only jump must be `jnz 0`, as the very last instruction.
+    NotALoop,
+    /// There is a jump somewhere other than the end.
+    InnerJump { position: usize },
+    /// There must be exactly one `adv`, shifting A by 3 bits.
+    ShiftCount(usize),
+    /// The `adv` shifts by something other than 3 bits.
+    ShiftAmount { position: usize },
+    /// There must be exactly one `out`.
+    OutputCount(usize),
+    /// B or C is read before it is written, so it depends on the previous iteration.
+    CarriedRegister { register: char, position: usize },
+    /// The shape is fine, but no value of A makes the program output itself.
+    NoQuine,
+}
+impl Display for UnsupportedProgram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnsupportedProgram::NotALoop => {
+                write!(f, "program must end with `jnz 0`, looping until A is zero")
+            }
+            UnsupportedProgram::InnerJump { position } => write!(
+                f,
+                "jump at position {} (only the final `jnz 0` is supported)",
+                position
+            ),
+            UnsupportedProgram::ShiftCount(count) => write!(
+                f,
+                "program has {} `adv` instructions, but must shift A exactly once per iteration",
+                count
+            ),
+            UnsupportedProgram::ShiftAmount { position } => write!(
+                f,
+                "`adv` at position {} must shift A by exactly 3 bits (`adv 3`)",
+                position
+            ),
+            UnsupportedProgram::OutputCount(count) => write!(
+                f,
+                "program has {} `out` instructions, but must output exactly once per iteration",
+                count
+            ),
+            UnsupportedProgram::CarriedRegister { register, position } => write!(
+                f,
+                "register {} is read at position {} before it is written, \
+                 so iterations depend on each other",
+                register, position
+            ),
+            UnsupportedProgram::NoQuine => {
+                write!(f, "no value of A makes the program output itself")
+            }
+        }
+    }
+}
+
+/// Check that the program is a loop that outputs once, and shifts A by 3 bits once, per iteration.
+fn check_shape(program: &Program) -> Result<(), UnsupportedProgram> {
+    let instructions: Vec<(usize, OpCode, ThreeBitValue)> = program
+        .0
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(index, pair)| (index * 2, OpCode::from(pair[0]), pair[1]))
+        .collect();
+    let Some((&(_, OpCode::Jnz, ThreeBitValue::Zero), body)) = instructions.split_last() else {
+        return Err(UnsupportedProgram::NotALoop);
+    };
+    if !program.0.len().is_multiple_of(2) {
+        return Err(UnsupportedProgram::NotALoop);
+    }
+    if let Some(&(position, ..)) = body.iter().find(|(_, opcode, _)| *opcode == OpCode::Jnz) {
+        return Err(UnsupportedProgram::InnerJump { position });
+    }
+
+    let shifts: Vec<_> = body
+        .iter()
+        .filter(|(_, opcode, _)| *opcode == OpCode::Adv)
+        .collect();
+    match shifts.as_slice() {
+        [(_, _, ThreeBitValue::Three)] => {}
+        [(position, ..)] => {
+            return Err(UnsupportedProgram::ShiftAmount {
+                position: *position,
+            })
+        }
+        _ => return Err(UnsupportedProgram::ShiftCount(shifts.len())),
+    }
+    let outputs = body
+        .iter()
+        .filter(|(_, opcode, _)| *opcode == OpCode::Out)
+        .count();
+    if outputs != 1 {
+        return Err(UnsupportedProgram::OutputCount(outputs));
+    }
+
+    // B and C must be derived from A within each iteration
+    let (mut b_written, mut c_written) = (false, false);
+    for &(position, opcode, operand) in body {
+        let combo = ComboOperand::from(operand);
+        let reads_b = match opcode {
+            OpCode::Bxl | OpCode::Bxc => true,
+            OpCode::Adv | OpCode::Bst | OpCode::Out | OpCode::Bdv | OpCode::Cdv => {
+                combo == ComboOperand::RegB
+            }
+            OpCode::Jnz => false,
+        };
+        let reads_c = match opcode {
+            OpCode::Bxc => true,
+            OpCode::Adv | OpCode::Bst | OpCode::Out | OpCode::Bdv | OpCode::Cdv => {
+                combo == ComboOperand::RegC
+            }
+            OpCode::Bxl | OpCode::Jnz => false,
+        };
+        if reads_b && !b_written {
+            return Err(UnsupportedProgram::CarriedRegister {
+                register: 'B',
+                position,
+            });
+        }
+        if reads_c && !c_written {
+            return Err(UnsupportedProgram::CarriedRegister {
+                register: 'C',
+                position,
+            });
+        }
+        b_written |= matches!(
+            opcode,
+            OpCode::Bxl | OpCode::Bst | OpCode::Bxc | OpCode::Bdv
+        );
+        c_written |= opcode == OpCode::Cdv;
+    }
+    Ok(())
+}
+
+/// Smallest initial value of A for which the program outputs itself.
+fn find_quine(program: &Program) -> Result<ArbitraryUInt, UnsupportedProgram> {
+    check_shape(program)?;
+
+    /// Depth-first, trying lower bits first, so the first complete candidate is the smallest.
+    fn extend(
+        program: &Program,
+        compiled: &CompiledProgram,
+        a: ArbitraryUInt,
+        matched: usize,
+    ) -> Option<ArbitraryUInt> {
+        if matched == program.0.len() {
+            return Some(a);
+        }
+        let suffix = &program.0[program.0.len() - matched - 1..];
+        (0..8)
+            .map(|bits| a << 3 | bits)
+            // A must not be zero before the loop ends, or it would end early
+            .filter(|&candidate| candidate != 0)
+            .filter(|&candidate| compiled.outputs(candidate, suffix))
+            .find_map(|candidate| extend(program, compiled, candidate, matched + 1))
+    }
+    extend(program, &CompiledProgram::compile(program), 0, 0).ok_or(UnsupportedProgram::NoQuine)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn program(values: &[u8]) -> Program {
+        Program(
+            values
+                .iter()
+                .map(|&v| v.try_into().expect("3 bit value"))
+                .collect(),
+        )
+    }
+
     #[test]
     fn example_solvable() {
         assert_eq!(super::solve(include_str!("EXAMPLE_P2")), 117440);
     }
 
+    #[test]
+    fn typical_input_shape_solvable() {
+        // bst a, bxl 1, cdv b, bxl 5, bxc, out b, adv 3, jnz 0
+        let program = program(&[2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 5, 5, 0, 3, 3, 0]);
+        let a = find_quine(&program).expect("has a quine");
+        let compiled = CompiledProgram::compile(&program);
+        assert_eq!(compiled.output_for(a), program.0);
+        assert!(
+            (0..a.min(1 << 16)).all(|smaller| !compiled.outputs(smaller, &program.0)),
+            "no small value is a quine either"
+        );
+    }
+
+    #[test]
+    fn unsupported_shapes_diagnosed() {
+        // EXAMPLE from part 1 shifts by a single bit
+        assert_eq!(
+            find_quine(&program(&[0, 1, 5, 4, 3, 0])),
+            Err(UnsupportedProgram::ShiftAmount { position: 0 })
+        );
+        assert_eq!(
+            find_quine(&program(&[0, 3, 5, 4])),
+            Err(UnsupportedProgram::NotALoop)
+        );
+        assert_eq!(
+            find_quine(&program(&[0, 3, 3, 0, 5, 4, 3, 0])),
+            Err(UnsupportedProgram::InnerJump { position: 2 })
+        );
+        assert_eq!(
+            find_quine(&program(&[0, 3, 0, 3, 5, 4, 3, 0])),
+            Err(UnsupportedProgram::ShiftCount(2))
+        );
+        assert_eq!(
+            find_quine(&program(&[0, 3, 3, 0])),
+            Err(UnsupportedProgram::OutputCount(0))
+        );
+        // B accumulates across iterations
+        assert_eq!(
+            find_quine(&program(&[1, 1, 0, 3, 5, 5, 3, 0])),
+            Err(UnsupportedProgram::CarriedRegister {
+                register: 'B',
+                position: 0
+            })
+        );
+        assert_eq!(
+            find_quine(&program(&[0, 3, 5, 1, 3, 0])),
+            Err(UnsupportedProgram::NoQuine),
+            "always outputs 1"
+        );
+    }
+
     #[ignore]
     #[test]
     fn input_solvable() {
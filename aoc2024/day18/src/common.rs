@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{hash_map, HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
 #[allow(unused_imports)]
 use winnow::{
@@ -85,3 +85,72 @@ impl<const SIZE: GraphDistance> Display for SetGraph<SIZE> {
         self.format(f)
     }
 }
+
+pub trait ExactSearch: Sized {
+    /// Graph with only the first `count` obstacles placed.
+    fn from_first_obstacles(all_obstacles: impl IntoIterator<Item = Pos>, count: usize) -> Self;
+    #[allow(dead_code)] // only p1 places a fixed amount
+    fn from_obstacles<const OBSTACLE_COUNT: usize>(
+        all_obstacles: impl IntoIterator<Item = Pos>,
+    ) -> Self {
+        Self::from_first_obstacles(all_obstacles, OBSTACLE_COUNT)
+    }
+    /// Steps of the fastest route from the top left to the bottom right, if there is one.
+    fn find_shortest_path(&self) -> Option<u64>;
+    /// Like [`ExactSearch::find_shortest_path`], but `0` if the goal can't be reached.
+    #[allow(dead_code)] // only p1 answers with the steps
+    fn find_shortest_path_through(&self) -> u64 {
+        self.find_shortest_path().unwrap_or(0)
+    }
+}
+impl<const SIZE: GraphDistance> ExactSearch for SetGraph<SIZE> {
+    fn from_first_obstacles(all_obstacles: impl IntoIterator<Item = Pos>, count: usize) -> Self {
+        SetGraph(all_obstacles.into_iter().take(count).collect())
+    }
+
+    fn find_shortest_path(&self) -> Option<u64> {
+        fn find_paths<const GRAPH_SIZE: GraphDistance>(
+            graph: &SetGraph<GRAPH_SIZE>,
+            start: Pos,
+            goal: Pos,
+        ) -> HashMap<Pos, Option<Pos>> {
+            let mut frontier = VecDeque::from([start]);
+            let mut came_from: HashMap<Pos, Option<Pos>> = HashMap::from([(start, None)]);
+
+            // create optimal path for every position
+            while let Some(current) = frontier.pop_front() {
+                if current == goal {
+                    break;
+                }
+
+                for valid_neighboor in graph.neighboors(&current) {
+                    if let hash_map::Entry::Vacant(new_entry) = came_from.entry(valid_neighboor) {
+                        new_entry.insert(Some(current));
+                        frontier.push_back(valid_neighboor);
+                    }
+                }
+            }
+            came_from
+        }
+
+        let start = Pos { x: 0, y: 0 };
+        let goal = Pos { x: SIZE, y: SIZE };
+        // neighboors skip corrupted positions, but the start is never a neighboor
+        if self.contains_obstacle(&start) {
+            return None;
+        }
+        let came_from = find_paths(self, start, goal);
+        if !came_from.contains_key(&goal) {
+            return None;
+        }
+
+        let mut prev = &goal;
+        let mut travel_distance = 0;
+        while let Some(Some(next_backwards_pos)) = came_from.get(prev) {
+            prev = next_backwards_pos;
+            travel_distance += 1;
+        }
+
+        Some(travel_distance)
+    }
+}
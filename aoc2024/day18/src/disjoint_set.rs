@@ -0,0 +1,62 @@
+/// Elements `0..len`, grouped into sets that can only ever be merged.
+/// Every set is a tree of parents, kept flat by path halving and union by size,
+/// so finding a set takes close to constant time.
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+impl DisjointSet {
+    /// Every element in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    /// Root element of the set containing `element`.
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    /// Merges the sets of `a` and `b`, returning whether they were apart.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (small, large) = match self.sizes[a] < self.sizes[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjointSet;
+
+    #[test]
+    fn unions_connect() {
+        let mut sets = DisjointSet::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.connected(1, 2));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert!(!sets.connected(4, 5));
+    }
+}
@@ -1,10 +1,12 @@
 #![doc = include_str!("../p2.md")]
 
 mod common;
+mod disjoint_set;
 
 use common::*;
-use itertools::{FoldWhile, Itertools};
-use std::collections::{HashSet, VecDeque};
+use disjoint_set::*;
+use itertools::Itertools;
+use std::collections::HashMap;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -16,19 +18,32 @@ use winnow::{
     {PResult, Parser},
 };
 
+/// `cargo run --bin p2 -- [input] [--bisect]`:
+/// `--bisect` searches for the first blocking byte by bisection instead, for comparison.
 fn main() {
-    util::DayInput::find::<18>().solve_with(solve::<STANDARD_SIZE>);
+    let bisect = util::has_flag("bisect");
+    util::day_input!().solve_with(|input| match bisect {
+        true => solve_by_bisection::<STANDARD_SIZE>(input),
+        false => solve::<STANDARD_SIZE>(input),
+    });
 }
 
-fn solve<const GRAPH_SIZE: GraphDistance>(input: impl AsRef<str>) -> Pos {
-    let obstacles = parse_bytes
-        .parse_next(&mut input.as_ref())
-        .expect("parsable");
-    let graph = UnfinalizedGraph::<GRAPH_SIZE> {
+fn parse_graph<const GRAPH_SIZE: GraphDistance>(input: &str) -> UnfinalizedGraph<GRAPH_SIZE> {
+    let obstacles = parse_bytes.parse_next(&mut &*input).expect("parsable");
+    UnfinalizedGraph::<GRAPH_SIZE> {
         all_obstacles: obstacles,
-    };
-    graph
-        .find_last_placed_before_impassable()
+    }
+}
+
+fn solve<const GRAPH_SIZE: GraphDistance>(input: impl AsRef<str>) -> Pos {
+    parse_graph::<GRAPH_SIZE>(input.as_ref())
+        .first_blocking_by_union_find()
+        .expect("some place")
+}
+
+fn solve_by_bisection<const GRAPH_SIZE: GraphDistance>(input: impl AsRef<str>) -> Pos {
+    parse_graph::<GRAPH_SIZE>(input.as_ref())
+        .first_blocking_by_bisection()
         .expect("some place")
 }
 
@@ -36,7 +51,13 @@ struct UnfinalizedGraph<const SIZE: GraphDistance> {
     all_obstacles: Vec<Pos>,
 }
 impl<const SIZE: GraphDistance> UnfinalizedGraph<SIZE> {
+    /// Searches for the goal again after every byte, once the first `SIZE` have fallen.
+    /// Kept to check the faster solvers against.
+    #[cfg(test)]
     fn find_last_placed_before_impassable(&self) -> Option<Pos> {
+        use itertools::FoldWhile;
+        use std::collections::{HashSet, VecDeque};
+
         /// BFS, early exit (when finding goal)
         fn can_find_goal_with_obstacles<const GRAPH_SIZE: GraphDistance>(
             graph: &SetGraph<GRAPH_SIZE>,
@@ -80,17 +101,111 @@ impl<const SIZE: GraphDistance> UnfinalizedGraph<SIZE> {
             .into_inner();
         first_obstacle_blocking
     }
+
+    /// Goes back in time instead: every byte has fallen, and they get removed again
+    /// starting with the last one. Every freed position joins the areas next to it,
+    /// until the byte whose removal connects start and goal, which is the first one blocking.
+    /// Every byte is handled once, at almost constant cost.
+    fn first_blocking_by_union_find(&self) -> Option<Pos> {
+        let index = |pos: &Pos| pos.y as usize * (SIZE as usize + 1) + pos.x as usize;
+        let (start, goal) = (Pos { x: 0, y: 0 }, Pos { x: SIZE, y: SIZE });
+        // bytes falling on an already corrupted position change nothing
+        let mut first_fallen = HashMap::new();
+        for (i, pos) in self.all_obstacles.iter().enumerate() {
+            first_fallen.entry(*pos).or_insert(i);
+        }
+        let mut graph = SetGraph::<SIZE>(first_fallen.keys().copied().collect());
+        let mut areas = DisjointSet::new((SIZE as usize + 1).pow(2));
+        for pos in (0..=SIZE)
+            .cartesian_product(0..=SIZE)
+            .map(|(y, x)| Pos { x, y })
+            .filter(|pos| !graph.contains_obstacle(pos))
+        {
+            for neighboor in graph.neighboors(&pos) {
+                areas.union(index(&pos), index(&neighboor));
+            }
+        }
+        if areas.connected(index(&start), index(&goal)) {
+            return None;
+        }
+        for (i, pos) in self.all_obstacles.iter().enumerate().rev() {
+            if first_fallen[pos] != i {
+                continue;
+            }
+            graph.0.remove(pos);
+            for neighboor in graph.neighboors(pos) {
+                areas.union(index(pos), index(&neighboor));
+            }
+            if areas.connected(index(&start), index(&goal)) {
+                return Some(*pos);
+            }
+        }
+        None
+    }
+
+    /// Once blocked, the goal stays blocked, so the amount of fallen bytes that first blocks it
+    /// can be bisected, with a search per guess.
+    fn first_blocking_by_bisection(&self) -> Option<Pos> {
+        let passable = |count| {
+            SetGraph::<SIZE>::from_first_obstacles(self.all_obstacles.iter().copied(), count)
+                .find_shortest_path()
+                .is_some()
+        };
+        let (mut passable_count, mut blocked_count) = (0, self.all_obstacles.len());
+        if passable(blocked_count) {
+            return None;
+        }
+        while blocked_count - passable_count > 1 {
+            let count = passable_count + (blocked_count - passable_count) / 2;
+            match passable(count) {
+                true => passable_count = count,
+                false => blocked_count = count,
+            }
+        }
+        Some(self.all_obstacles[blocked_count - 1])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::*;
+    use util::random::XorShift;
 
     #[test]
     fn example_solvable() {
         assert_eq!(solve::<6>(include_str!("EXAMPLE")).to_string(), "6,1");
     }
 
+    #[test]
+    fn example_solvers_agree() {
+        let graph = parse_graph::<6>(include_str!("EXAMPLE"));
+        let expected = Some(Pos { x: 6, y: 1 });
+        assert_eq!(graph.find_last_placed_before_impassable(), expected);
+        assert_eq!(graph.first_blocking_by_union_find(), expected);
+        assert_eq!(graph.first_blocking_by_bisection(), expected);
+    }
+
+    #[test]
+    fn random_solvers_agree() {
+        let mut random = XorShift::default();
+        for byte_count in (0..100).map(|n| n % 40) {
+            // positions repeat, and the start or goal may get corrupted
+            let all_obstacles = (0..byte_count)
+                .map(|_| Pos {
+                    x: random.below(7) as GraphDistance,
+                    y: random.below(7) as GraphDistance,
+                })
+                .collect::<Vec<_>>();
+            let graph = UnfinalizedGraph::<6> { all_obstacles };
+            assert_eq!(
+                graph.first_blocking_by_union_find(),
+                graph.first_blocking_by_bisection(),
+                "{:?}",
+                graph.all_obstacles
+            );
+        }
+    }
+
     #[ignore]
     #[test]
     fn input_solvable() {
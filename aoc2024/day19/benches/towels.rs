@@ -0,0 +1,85 @@
+//! Counting arrangements with hash sets of patterns versus a pattern trie,
+//! for the example and for many patterns with long designs.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::collections::HashSet;
+use std::hint::black_box;
+use util::random::XorShift;
+use winnow::Parser;
+
+#[path = "../src/common.rs"]
+mod common;
+#[allow(dead_code)] // only counting arrangements is compared here
+#[path = "../src/hash_set.rs"]
+mod hash_set;
+#[path = "../src/trie.rs"]
+mod trie;
+
+use common::*;
+use trie::*;
+
+/// `pattern_count` random patterns of 5 to 10 stripes, and designs stitched together from them,
+/// so that every design is possible.
+fn generated(
+    pattern_count: usize,
+    design_count: usize,
+    design_len: usize,
+) -> (HashSet<StripePattern>, Vec<StripePattern>) {
+    let mut random = XorShift::default();
+    let mut random = move |below: usize| random.below(below as u64) as usize;
+    let colors = "wubrg".as_bytes();
+    let patterns = (0..pattern_count)
+        .map(|_| {
+            let len = 5 + random(6);
+            (0..len)
+                .map(|_| colors[random(colors.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    let designs = (0..design_count).map(|_| {
+        let mut design = String::new();
+        while design.len() < design_len {
+            design.push_str(&patterns[random(patterns.len())]);
+        }
+        design
+    });
+    let input = format!(
+        "{}\n\n{}",
+        patterns.join(", "),
+        designs.collect::<Vec<_>>().join("\n")
+    );
+    parse_input.parse(&input).expect("parsable")
+}
+
+fn arrangements(c: &mut Criterion) {
+    let inputs = [
+        (
+            "example",
+            parse_input
+                .parse(include_str!("../src/EXAMPLE"))
+                .expect("parsable"),
+        ),
+        ("2000 patterns, 100 stripes", generated(2000, 20, 100)),
+        ("2000 patterns, 1000 stripes", generated(2000, 20, 1000)),
+    ];
+    let mut group = c.benchmark_group("day19 arrangements");
+    group.sample_size(10);
+    for (name, (available, designs)) in &inputs {
+        group.bench_with_input(BenchmarkId::new("hash sets", name), name, |b, _| {
+            b.iter(|| hash_set::count_arrangements(black_box(available), designs.clone()))
+        });
+        group.bench_with_input(BenchmarkId::new("trie", name), name, |b, _| {
+            b.iter(|| {
+                let trie = black_box(available).iter().collect::<PatternTrie>();
+                designs
+                    .iter()
+                    .map(|design| trie.arrangements(&design.0).ways)
+                    .fold(0u64, u64::wrapping_add)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, arrangements);
+criterion_main!(benches);
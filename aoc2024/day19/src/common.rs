@@ -23,7 +23,7 @@ fn parse_designs(input: &mut &str) -> PResult<Vec<StripePattern>> {
     separated(1.., parse_pattern, line_ending).parse_next(input)
 }
 
-fn parse_pattern(input: &mut &str) -> PResult<StripePattern> {
+pub fn parse_pattern(input: &mut &str) -> PResult<StripePattern> {
     repeat(1.., parse_color)
         .map(StripePattern)
         .parse_next(input)
@@ -41,27 +41,6 @@ fn parse_color(input: &mut &str) -> PResult<StripeColor> {
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct StripePattern(pub Vec<StripeColor>);
-impl StripePattern {
-    pub fn single_removed_sub_patterns<'p>(
-        &'p self,
-        full_set: &'p HashSet<StripePattern>,
-    ) -> impl Iterator<Item = StripePattern> + 'p {
-        full_set.iter().filter_map(|existing| {
-            // pattern order must be preserved, we can only remove from the edges
-            if self.0.starts_with(&existing.0[..]) {
-                let len = existing.0.len();
-                let sub_inner: Vec<_> = self.0[len..].into();
-                if !sub_inner.is_empty() {
-                    Some(StripePattern(sub_inner)) // pattern without this start
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-    }
-}
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub enum StripeColor {
@@ -77,6 +56,14 @@ pub enum StripeColor {
     Green,
 }
 
+impl StripeColor {
+    pub const COUNT: usize = 5;
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
 impl Display for StripeColor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
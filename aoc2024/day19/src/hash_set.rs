@@ -0,0 +1,103 @@
+//! Matching designs by removing known patterns from their start, with hash sets of patterns.
+//! Superseded by [`crate::trie`], but kept around to compare against.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::common::*;
+
+/// Towels possible with the available patterns, see [`compress`].
+pub fn count_possible(available: HashSet<StripePattern>, designs: Vec<StripePattern>) -> usize {
+    let compressed = compress(available.clone());
+    let mut cache = available;
+    designs
+        .into_iter()
+        .filter(|design| design.clone().test_possible_cached(&compressed, &mut cache))
+        .count()
+}
+
+/// Sum of the arrangements of every design with the available patterns.
+pub fn count_arrangements(available: &HashSet<StripePattern>, designs: Vec<StripePattern>) -> u64 {
+    let mut known_values = HashMap::new();
+    designs
+        .into_iter()
+        .map(|design| design.count_possible_cached(available, &mut known_values))
+        .sum()
+}
+
+pub fn compress(raw_patterns: HashSet<StripePattern>) -> HashSet<StripePattern> {
+    raw_patterns
+        .clone()
+        // order of "removal" (or iteration) does not matter
+        .into_iter()
+        .filter(|p| {
+            // keep this pattern if there are no subpatterns that also exist
+            !p.single_removed_sub_patterns(&raw_patterns)
+                .any(|sub_pattern| raw_patterns.contains(&sub_pattern))
+        })
+        .collect()
+}
+
+impl StripePattern {
+    pub fn single_removed_sub_patterns<'p>(
+        &'p self,
+        full_set: &'p HashSet<StripePattern>,
+    ) -> impl Iterator<Item = StripePattern> + 'p {
+        full_set.iter().filter_map(|existing| {
+            // pattern order must be preserved, we can only remove from the edges
+            if self.0.starts_with(&existing.0[..]) {
+                let len = existing.0.len();
+                let sub_inner: Vec<_> = self.0[len..].into();
+                if !sub_inner.is_empty() {
+                    Some(StripePattern(sub_inner)) // pattern without this start
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn test_possible_cached(
+        self,
+        compressed: &HashSet<StripePattern>,
+        validated: &mut HashSet<StripePattern>,
+    ) -> bool {
+        if validated.contains(&self) {
+            true
+        } else if self
+            .single_removed_sub_patterns(compressed)
+            .any(|sub_pattern| sub_pattern.test_possible_cached(compressed, validated))
+        // a valid subtree could create pattern
+        {
+            // remember for other subtree branches or top level tests
+            validated.insert(self);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn count_possible_cached(
+        self,
+        initial: &HashSet<StripePattern>,
+        known_values: &mut HashMap<StripePattern, u64>,
+    ) -> u64 {
+        if let Some(&num) = known_values.get(&self) {
+            num
+        } else {
+            let subpattern_ways: u64 = self
+                .single_removed_sub_patterns(initial)
+                .map(|sub_pattern| sub_pattern.count_possible_cached(initial, known_values))
+                .sum();
+            let this_ways = if initial.contains(&self) {
+                subpattern_ways + 1
+            } else {
+                subpattern_ways
+            };
+            // we now know the amount of ways to make this, reuse for other purposes
+            known_values.insert(self, this_ways);
+            this_ways
+        }
+    }
+}
@@ -1,14 +1,16 @@
 #![doc = include_str!("../p1.md")]
 
-use itertools::Itertools;
-use std::collections::HashSet;
 use winnow::Parser;
 
 mod common;
+#[cfg(test)]
+mod hash_set;
+mod trie;
 use common::*;
+use trie::*;
 
 fn main() {
-    util::DayInput::find::<19>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
@@ -27,58 +29,21 @@ fn main() {
 /// A strategy would be to remove composite values from the patterns tried for each design.
 /// The caching we use is the initial + all discovered composites. We should not remove anything
 /// from this cache.
+/// ## Trie
+/// Removing patterns from the start of the design still works, but trying every pattern for
+/// every position is slow with many patterns. Walking the design along a [`PatternTrie`] tries
+/// every pattern starting at a position at once, see [`PatternTrie::arrangements`].
 fn solve(input: impl AsRef<str>) -> usize {
-    let (initial_available, wanted_designs) = parse_input
+    let (available, wanted_designs) = parse_input
         .parse_next(&mut input.as_ref())
         .expect("parsable");
-    let compressed = compress(initial_available.clone());
-    let mut cache = initial_available;
+    let trie = available.iter().collect::<PatternTrie>();
     wanted_designs
-        .into_iter()
-        .enumerate()
-        .map(|(i, design)| {
-            eprintln!("{}: {}", i, design.0.iter().join(""));
-            design.test_possible_cached(&compressed, &mut cache)
-        })
-        .filter(|&result| result)
+        .iter()
+        .filter(|design| trie.arrangements(&design.0).possible)
         .count()
 }
 
-fn compress(raw_patterns: HashSet<StripePattern>) -> HashSet<StripePattern> {
-    raw_patterns
-        .clone()
-        // order of "removal" (or iteration) does not matter
-        .into_iter()
-        .filter(|p| {
-            // keep this pattern if there are no subpatterns that also exist
-            !p.single_removed_sub_patterns(&raw_patterns)
-                .any(|sub_pattern| raw_patterns.contains(&sub_pattern))
-        })
-        .collect()
-}
-
-impl StripePattern {
-    fn test_possible_cached(
-        self,
-        compressed: &HashSet<StripePattern>,
-        validated: &mut HashSet<StripePattern>,
-    ) -> bool {
-        if validated.contains(&self) {
-            true
-        } else if self
-            .single_removed_sub_patterns(compressed)
-            .any(|sub_pattern| sub_pattern.test_possible_cached(compressed, validated))
-        // a valid subtree could create pattern
-        {
-            // remember for other subtree branches or top level tests
-            validated.insert(self);
-            true
-        } else {
-            false
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     #[test]
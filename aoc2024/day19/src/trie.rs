@@ -0,0 +1,181 @@
+//! Matching every pattern at once, by walking a prefix tree of all patterns along the design.
+
+use crate::common::*;
+
+/// Whether a design can be made, and in how many ways.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Arrangements {
+    pub possible: bool,
+    /// Stops counting at [`u64::MAX`], which doesn't affect [`Arrangements::possible`].
+    pub ways: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Next node for every [`StripeColor`], by [`StripeColor::index`].
+    children: [Option<usize>; StripeColor::COUNT],
+    /// Whether the colors leading here are an available pattern.
+    pattern_end: bool,
+}
+
+/// Every available pattern, sharing nodes for shared starts.
+#[derive(Debug, Clone)]
+pub struct PatternTrie {
+    nodes: Vec<Node>,
+}
+
+impl<'p> FromIterator<&'p StripePattern> for PatternTrie {
+    fn from_iter<T: IntoIterator<Item = &'p StripePattern>>(patterns: T) -> Self {
+        let mut trie = PatternTrie {
+            nodes: vec![Node::default()],
+        };
+        for pattern in patterns {
+            trie.insert(&pattern.0);
+        }
+        trie
+    }
+}
+
+impl PatternTrie {
+    pub fn insert(&mut self, pattern: &[StripeColor]) {
+        let mut node = 0;
+        for color in pattern {
+            node = match self.nodes[node].children[color.index()] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[color.index()] = Some(child);
+                    child
+                }
+            };
+        }
+        self.nodes[node].pattern_end = true;
+    }
+
+    /// Lengths of every pattern the design starts with, shortest first.
+    fn prefix_lengths<'d>(&'d self, design: &'d [StripeColor]) -> impl Iterator<Item = usize> + 'd {
+        design
+            .iter()
+            .scan(0, |node, color| {
+                *node = self.nodes[*node].children[color.index()]?;
+                Some(*node)
+            })
+            .enumerate()
+            .filter(|&(_, node)| self.nodes[node].pattern_end)
+            .map(|(i, _)| i + 1)
+    }
+
+    /// Scans the design once: the ways to make the design up to every position are passed on
+    /// to every position a pattern starting there reaches.
+    /// Takes the design length times the longest pattern length, however many patterns there are.
+    pub fn arrangements(&self, design: &[StripeColor]) -> Arrangements {
+        let mut possible = vec![false; design.len() + 1];
+        let mut ways = vec![0u64; design.len() + 1];
+        possible[0] = true;
+        ways[0] = 1;
+        for start in 0..design.len() {
+            if !possible[start] {
+                continue;
+            }
+            for len in self.prefix_lengths(&design[start..]) {
+                possible[start + len] = true;
+                ways[start + len] = ways[start + len].saturating_add(ways[start]);
+            }
+        }
+        Arrangements {
+            possible: possible[design.len()],
+            ways: ways[design.len()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winnow::Parser;
+
+    use super::*;
+
+    #[allow(dead_code)] // the benches drop the `#[test]` functions using it
+    fn patterns(input: &str) -> Vec<StripePattern> {
+        input
+            .split(", ")
+            .map(|pattern| parse_pattern.parse(pattern).expect("parsable"))
+            .collect()
+    }
+
+    #[test]
+    fn example_arrangements() {
+        let (available, designs) = parse_input
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let trie = available.iter().collect::<PatternTrie>();
+        let ways = designs
+            .iter()
+            .map(|design| trie.arrangements(&design.0).ways)
+            .collect::<Vec<_>>();
+        assert_eq!(ways, [2, 1, 4, 6, 0, 1, 2, 0]);
+        for (design, ways) in designs.iter().zip(ways) {
+            assert_eq!(trie.arrangements(&design.0).possible, ways != 0);
+        }
+    }
+
+    #[test]
+    fn overflowing_ways_still_possible() {
+        let trie = patterns("w, ww").iter().collect::<PatternTrie>();
+        // fibonacci numbers, far beyond `u64`
+        let design = patterns(&"w".repeat(200)).remove(0);
+        let arrangements = trie.arrangements(&design.0);
+        assert_eq!(arrangements.ways, u64::MAX);
+        assert!(arrangements.possible);
+        let design = patterns(&format!("{}u", "w".repeat(200))).remove(0);
+        assert_eq!(
+            trie.arrangements(&design.0),
+            Arrangements {
+                possible: false,
+                ways: 0
+            }
+        );
+    }
+
+    #[test]
+    fn hash_sets_agree() {
+        // imported here, since the benches build this module with `--cfg test` but drop `#[test]` functions
+        use crate::hash_set;
+        use std::collections::HashSet;
+        use util::random::XorShift;
+
+        let mut random = XorShift::default();
+        let colors = "wubrg".chars().collect::<Vec<_>>();
+        let mut random_stripes = |len: u64| {
+            (0..len)
+                // only a few colors, so that patterns overlap a lot
+                .map(|_| colors[random.below(3) as usize])
+                .collect::<String>()
+        };
+        for _ in 0..20 {
+            let available = (0..8)
+                .map(|n| random_stripes(1 + n % 4))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let available = patterns(&available).into_iter().collect::<HashSet<_>>();
+            let designs = (0..10)
+                .map(|n| random_stripes(5 + n))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let designs = patterns(&designs);
+            let trie = available.iter().collect::<PatternTrie>();
+            for design in designs {
+                let arrangements = trie.arrangements(&design.0);
+                assert_eq!(
+                    arrangements.possible,
+                    hash_set::count_possible(available.clone(), vec![design.clone()]) == 1
+                );
+                assert_eq!(
+                    arrangements.ways,
+                    hash_set::count_arrangements(&available, vec![design])
+                );
+            }
+        }
+    }
+}
@@ -12,7 +12,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<2>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// Each line is a report,
@@ -5,7 +5,7 @@ use itertools::Itertools;
 mod common;
 
 fn main() {
-    util::DayInput::find::<2>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 fn solve(input: impl AsRef<str>) -> u32 {
@@ -0,0 +1,333 @@
+//! Chains of keypads: robots pressing buttons of the next keypad, controlled by directional keypads.
+//!
+//! Between two presses on a keypad, every robot above it returns to `A` (to press it).
+//! So the cost of a move on some keypad only depends on the two buttons and the amount of
+//! keypads above it, which makes it cacheable per `(from, to, depth)` no matter how deep the chain.
+//! Which moves are worth considering is derived from the layouts of the keypads (see `layout`).
+
+use crate::common::*;
+use crate::layout::*;
+use anyhow::anyhow;
+use std::collections::HashMap;
+
+/// Numeric keypad, pressed by a robot, controlled through `robots` directional keypads,
+/// of which we press the outermost one.
+pub struct KeypadChain {
+    robots: usize,
+    numeric: Layout,
+    directional: Layout,
+    /// Presses we need for some robot to move from one button to another and press it,
+    /// with some amount of directional keypads above.
+    cache: HashMap<(DirButton, DirButton, usize), u64>,
+}
+impl KeypadChain {
+    /// The keypads of the puzzle.
+    pub fn new(robots: usize) -> Self {
+        KeypadChain {
+            robots,
+            numeric: NumButton::layout().clone(),
+            directional: DirButton::layout().clone(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Custom keypads, where the directional one has to have the buttons `^`, `v`, `<`, `>` and `A`.
+    #[allow(dead_code)] // only the tests load custom layouts
+    pub fn with_layouts(
+        robots: usize,
+        numeric: Layout,
+        directional: Layout,
+    ) -> anyhow::Result<Self> {
+        let mut labels = directional.labels().collect::<Vec<_>>();
+        labels.sort();
+        if labels != ['<', '>', 'A', '^', 'v'] {
+            return Err(anyhow!("directional keypad needs exactly ^, v, <, > and A"));
+        }
+        Ok(KeypadChain {
+            robots,
+            numeric,
+            directional,
+            cache: HashMap::new(),
+        })
+    }
+
+    /// Presses we need for pressing `sequence` on a directional keypad `depth` keypads down.
+    fn presses(&mut self, sequence: &[DirButton], depth: usize) -> u64 {
+        if depth == 0 {
+            return sequence.len() as u64;
+        }
+        let mut from = DirButton::Accept;
+        sequence
+            .iter()
+            .map(|&to| {
+                let presses = self.move_presses(from, to, depth);
+                from = to;
+                presses
+            })
+            .sum()
+    }
+
+    fn move_presses(&mut self, from: DirButton, to: DirButton, depth: usize) -> u64 {
+        if let Some(&presses) = self.cache.get(&(from, to, depth)) {
+            return presses;
+        }
+        let presses = self
+            .directional
+            .moves(from.label(), to.label())
+            .iter()
+            .map(|sequence| self.presses(sequence, depth - 1))
+            .min()
+            .expect("buttons are reachable from each other");
+        self.cache.insert((from, to, depth), presses);
+        presses
+    }
+
+    /// Pairs of buttons to move between, starting from `A`.
+    fn code_buttons(code: &str) -> impl Iterator<Item = (char, char)> + '_ {
+        "A".chars().chain(code.chars()).zip(code.chars())
+    }
+
+    /// Length of the shortest sequence we can press to have the buttons pressed on the numeric keypad.
+    pub fn presses_for(&mut self, code: &str) -> u64 {
+        Self::code_buttons(code)
+            .map(|(from, to)| {
+                self.numeric
+                    .moves(from, to)
+                    .iter()
+                    .map(|sequence| self.presses(sequence, self.robots))
+                    .min()
+                    .expect("buttons are reachable from each other")
+            })
+            .sum()
+    }
+
+    pub fn code_presses(&mut self, code: &Code) -> u64 {
+        self.presses_for(&code.labels())
+    }
+
+    pub fn complexity(&mut self, code: &Code) -> u64 {
+        self.code_presses(code) * code.to_number() as u64
+    }
+
+    /// Some shortest sequence we can press to have the buttons pressed on the numeric keypad.
+    /// Its length is [`KeypadChain::presses_for`], so this is only feasible for few robots.
+    pub fn sequence_for(&mut self, code: &str) -> Sequence {
+        let mut sequence = vec![];
+        for (from, to) in Self::code_buttons(code) {
+            let best = self
+                .numeric
+                .moves(from, to)
+                .into_iter()
+                .min_by_key(|moves| self.presses(moves, self.robots))
+                .expect("buttons are reachable from each other");
+            sequence.extend(self.expand(&best, self.robots));
+        }
+        Sequence(sequence)
+    }
+
+    pub fn sequence(&mut self, code: &Code) -> Sequence {
+        self.sequence_for(&code.labels())
+    }
+
+    /// Some shortest sequence to press for `sequence` to be pressed `depth` keypads down.
+    fn expand(&mut self, sequence: &[DirButton], depth: usize) -> Vec<DirButton> {
+        if depth == 0 {
+            return sequence.to_vec();
+        }
+        let mut from = DirButton::Accept;
+        let mut expanded = vec![];
+        for &to in sequence {
+            let best = self
+                .directional
+                .moves(from.label(), to.label())
+                .into_iter()
+                .min_by_key(|moves| self.presses(moves, depth - 1))
+                .expect("buttons are reachable from each other");
+            expanded.extend(self.expand(&best, depth - 1));
+            from = to;
+        }
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use std::collections::{HashSet, VecDeque};
+    use winnow::Parser;
+
+    fn codes(input: &str) -> Vec<Code> {
+        parse_sequences.parse_next(&mut &*input).expect("parsable")
+    }
+
+    /// Arms of the robots on the directional keypads (outermost first), and on the numeric keypad.
+    type Arms = (Vec<Position>, Position);
+
+    /// Press a button on the outermost keypad,
+    /// returning where the arms are afterwards and what got pressed on the numeric keypad.
+    fn press(
+        (directional_arms, numeric_arm): &Arms,
+        button: char,
+        numeric: &Layout,
+        directional: &Layout,
+    ) -> Option<(Arms, Option<char>)> {
+        let step = |(row, column): Position, button| match button {
+            '^' => (row - 1, column),
+            'v' => (row + 1, column),
+            '<' => (row, column - 1),
+            '>' => (row, column + 1),
+            _ => unreachable!("only directions move"),
+        };
+        let mut directional_arms = directional_arms.clone();
+        let mut button = button;
+        for arm in directional_arms.iter_mut() {
+            if button != 'A' {
+                *arm = step(*arm, button);
+                directional.label(*arm)?;
+                return Some(((directional_arms, *numeric_arm), None));
+            }
+            button = directional.label(*arm).expect("arms only point at buttons");
+        }
+        if button != 'A' {
+            let numeric_arm = step(*numeric_arm, button);
+            numeric.label(numeric_arm)?;
+            return Some(((directional_arms, numeric_arm), None));
+        }
+        Some((
+            (directional_arms, *numeric_arm),
+            numeric.label(*numeric_arm),
+        ))
+    }
+
+    fn start(robots: usize, numeric: &Layout, directional: &Layout) -> Arms {
+        let a = |layout: &Layout| layout.position('A').expect("has A");
+        (vec![a(directional); robots], a(numeric))
+    }
+
+    /// Press `sequence` on the outermost keypad, returning what gets pressed on the numeric one.
+    fn enter(sequence: &Sequence, robots: usize, numeric: &Layout, directional: &Layout) -> String {
+        let mut arms = start(robots, numeric, directional);
+        let mut entered = String::new();
+        for button in &sequence.0 {
+            let (next, pressed) =
+                press(&arms, button.label(), numeric, directional).expect("never over a gap");
+            arms = next;
+            entered.extend(pressed);
+        }
+        entered
+    }
+
+    /// Breadth-first search through every position of every arm, feasible for few robots only.
+    fn brute_force(code: &str, robots: usize, numeric: &Layout, directional: &Layout) -> u64 {
+        let code = code.chars().collect_vec();
+        let start = (start(robots, numeric, directional), 0);
+        let mut seen = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some(((arms, entered), presses)) = queue.pop_front() {
+            if entered == code.len() {
+                return presses;
+            }
+            for button in ['^', 'v', '<', '>', 'A'] {
+                let Some((arms, pressed)) = press(&arms, button, numeric, directional) else {
+                    continue;
+                };
+                let entered = match pressed {
+                    None => entered,
+                    Some(pressed) if pressed == code[entered] => entered + 1,
+                    Some(_) => continue,
+                };
+                if seen.insert((arms.clone(), entered)) {
+                    queue.push_back(((arms, entered), presses + 1));
+                }
+            }
+        }
+        panic!("code cannot be entered")
+    }
+
+    fn custom_layouts() -> (Layout, Layout) {
+        let (numeric, directional) = include_str!("LAYOUTS")
+            .split_once("\n\n")
+            .expect("two layouts");
+        (
+            Layout::parse(numeric).expect("valid"),
+            Layout::parse(directional).expect("valid"),
+        )
+    }
+
+    #[test]
+    fn sequences_as_short_as_example() {
+        let (numeric, directional) = (NumButton::layout(), DirButton::layout());
+        let shortest = include_str!("EXAMPLE_SHORTEST");
+        for (code, line) in codes(include_str!("EXAMPLE")).iter().zip(shortest.lines()) {
+            let (entered, expected) = line.split_once(": ").expect("code: sequence");
+            let sequence = KeypadChain::new(2).sequence(code);
+            assert_eq!(sequence.0.len(), expected.len(), "{}", entered);
+            assert_eq!(enter(&sequence, 2, numeric, directional), entered);
+            assert_eq!(
+                KeypadChain::new(2).code_presses(code),
+                expected.len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn sequences_enter_codes_through_more_robots() {
+        let (numeric, directional) = (NumButton::layout(), DirButton::layout());
+        for robots in 0..5 {
+            let mut chain = KeypadChain::new(robots);
+            for code in codes(include_str!("EXAMPLE")) {
+                let sequence = chain.sequence(&code);
+                assert_eq!(sequence.0.len() as u64, chain.code_presses(&code));
+                assert_eq!(
+                    enter(&sequence, robots, numeric, directional),
+                    code.labels()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn puzzle_keypads_match_brute_force() {
+        let (numeric, directional) = (NumButton::layout(), DirButton::layout());
+        for robots in 0..3 {
+            let mut chain = KeypadChain::new(robots);
+            for code in ["029A", "980A", "179A", "456A", "379A", "A", "70A"] {
+                assert_eq!(
+                    chain.presses_for(code),
+                    brute_force(code, robots, numeric, directional),
+                    "{} through {} robots",
+                    code,
+                    robots
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn custom_keypads_match_brute_force() {
+        let (numeric, directional) = custom_layouts();
+        for robots in 0..3 {
+            let mut chain = KeypadChain::with_layouts(robots, numeric.clone(), directional.clone())
+                .expect("valid keypads");
+            for code in ["029A", "980A", "179A", "456A", "379A", "10A"] {
+                let sequence = chain.sequence_for(code);
+                assert_eq!(enter(&sequence, robots, &numeric, &directional), code);
+                assert_eq!(
+                    chain.presses_for(code),
+                    brute_force(code, robots, &numeric, &directional),
+                    "{} through {} robots",
+                    code,
+                    robots
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn directional_keypad_needs_every_direction() {
+        let (numeric, _) = custom_layouts();
+        let directional = Layout::parse("<^>A").expect("valid");
+        assert!(KeypadChain::with_layouts(2, numeric, directional).is_err());
+    }
+}
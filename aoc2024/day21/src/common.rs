@@ -0,0 +1,473 @@
+use crate::chain::KeypadChain;
+use crate::layout::*;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::sync::LazyLock;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+pub struct Sequence(pub Vec<DirButton>);
+impl Display for Sequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().join(""))
+    }
+}
+
+/// I assume all codes are three digits long?
+/// It is that long for my input and for the examples.
+pub struct Code(pub [Num; 3]);
+impl Code {
+    /// Some shortest sequence for us to press, through `robots` directional keypads.
+    pub fn generate_optimal_sequence(&self, robots: usize) -> Sequence {
+        KeypadChain::new(robots).sequence(self)
+    }
+    /// Buttons to press on the numeric keypad, e.g. `029A`.
+    pub fn labels(&self) -> String {
+        format!("{}A", self.0.iter().join(""))
+    }
+    pub fn to_number(&self) -> u32 {
+        self.0
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, val)| 10u32.pow(i as u32) * u32::from(val))
+            .sum()
+    }
+}
+impl Display for Code {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.labels(),
+            // as many robots as in part one, like in `EXAMPLE_SHORTEST`
+            self.generate_optimal_sequence(2)
+        )
+    }
+}
+
+pub fn parse_sequences(input: &mut &str) -> PResult<Vec<Code>> {
+    separated(1.., parse_sequence, ('A', line_ending)).parse_next(input)
+}
+pub fn parse_sequence(input: &mut &str) -> PResult<Code> {
+    (parse_num, parse_num, parse_num)
+        .map(|(a, b, c)| Code([a, b, c]))
+        .parse_next(input)
+}
+pub fn parse_num(input: &mut &str) -> PResult<Num> {
+    let mut num_parser = dispatch! {any;
+        '1' => empty.value(Num::One),
+        '2' => empty.value(Num::Two),
+        '3' => empty.value(Num::Three),
+        '4' => empty.value(Num::Four),
+        '5' => empty.value(Num::Five),
+        '6' => empty.value(Num::Six),
+        '7' => empty.value(Num::Seven),
+        '8' => empty.value(Num::Eight),
+        '9' => empty.value(Num::Nine),
+        '0' => empty.value(Num::Zero),
+        _ => fail,
+    };
+    num_parser.parse_next(input)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Num {
+    One,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Zero,
+}
+impl Display for Num {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", u32::from(self))
+    }
+}
+impl From<&Num> for u32 {
+    fn from(value: &Num) -> Self {
+        match value {
+            Num::One => 1,
+            Num::Two => 2,
+            Num::Three => 3,
+            Num::Four => 4,
+            Num::Five => 5,
+            Num::Six => 6,
+            Num::Seven => 7,
+            Num::Eight => 8,
+            Num::Nine => 9,
+            Num::Zero => 0,
+        }
+    }
+}
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[allow(dead_code)] // codes are solved by their labels, only the tests name buttons
+pub enum NumButton {
+    Num(Num),
+    Accept,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DirButton {
+    Dir(Dir),
+    Accept,
+}
+impl Display for DirButton {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Row (downwards) and column of a button.
+pub type Position = (i8, i8);
+
+/// Buttons of a keypad with a known layout.
+pub trait Keypad: Copy + Eq {
+    fn layout() -> &'static Layout;
+    fn label(&self) -> char;
+    #[allow(dead_code)] // the chain looks up moves by label, only the tests ask for positions
+    fn position(&self) -> Position {
+        Self::layout()
+            .position(self.label())
+            .expect("button of its own layout")
+    }
+}
+static NUMERIC: LazyLock<Layout> =
+    LazyLock::new(|| Layout::parse(NUMERIC_LAYOUT).expect("valid layout"));
+static DIRECTIONAL: LazyLock<Layout> =
+    LazyLock::new(|| Layout::parse(DIRECTIONAL_LAYOUT).expect("valid layout"));
+impl Keypad for NumButton {
+    fn layout() -> &'static Layout {
+        &NUMERIC
+    }
+    fn label(&self) -> char {
+        match self {
+            NumButton::Num(num) => char::from_digit(u32::from(num), 10).expect("digit"),
+            NumButton::Accept => 'A',
+        }
+    }
+}
+impl Keypad for DirButton {
+    fn layout() -> &'static Layout {
+        &DIRECTIONAL
+    }
+    fn label(&self) -> char {
+        match self {
+            DirButton::Dir(Dir::Up) => '^',
+            DirButton::Dir(Dir::Down) => 'v',
+            DirButton::Dir(Dir::Left) => '<',
+            DirButton::Dir(Dir::Right) => '>',
+            DirButton::Accept => 'A',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type KeypadDistance = u8;
+    trait TravelCost {
+        fn a_to_b_cost(&self, other: &Self) -> KeypadDistance;
+    }
+    /// Derived from the layout, as the length of the shortest moves.
+    impl<K: Keypad> TravelCost for K {
+        fn a_to_b_cost(&self, other: &Self) -> KeypadDistance {
+            let moves = Self::layout().moves(self.label(), other.label());
+            (moves[0].len() - 1) as KeypadDistance
+        }
+    }
+
+    /// The tables that were written out by hand before costs were derived from layouts.
+    #[rustfmt::skip]
+    fn hand_written_num_cost(this: &NumButton, other: &NumButton) -> KeypadDistance {
+        match this {
+            NumButton::Num(Num::One) => match other {
+                NumButton::Num(Num::One) => 0,
+                NumButton::Num(Num::Two) => 1,
+                NumButton::Num(Num::Three) => 2,
+                NumButton::Num(Num::Four) => 1,
+                NumButton::Num(Num::Five) => 2,
+                NumButton::Num(Num::Six) => 3,
+                NumButton::Num(Num::Seven) => 2,
+                NumButton::Num(Num::Eight) => 3,
+                NumButton::Num(Num::Nine) => 4,
+                NumButton::Num(Num::Zero) => 2,
+                NumButton::Accept => 3,
+            },
+            NumButton::Num(Num::Two) => match other {
+                NumButton::Num(Num::One) => 1,
+                NumButton::Num(Num::Two) => 0,
+                NumButton::Num(Num::Three) => 1,
+                NumButton::Num(Num::Four) => 2,
+                NumButton::Num(Num::Five) => 1,
+                NumButton::Num(Num::Six) => 2,
+                NumButton::Num(Num::Seven) => 3,
+                NumButton::Num(Num::Eight) => 2,
+                NumButton::Num(Num::Nine) => 3,
+                NumButton::Num(Num::Zero) => 1,
+                NumButton::Accept => 2,
+            },
+            NumButton::Num(Num::Three) => match other {
+                NumButton::Num(Num::One) => 2,
+                NumButton::Num(Num::Two) => 1,
+                NumButton::Num(Num::Three) => 0,
+                NumButton::Num(Num::Four) => 3,
+                NumButton::Num(Num::Five) => 2,
+                NumButton::Num(Num::Six) => 1,
+                NumButton::Num(Num::Seven) => 4,
+                NumButton::Num(Num::Eight) => 3,
+                NumButton::Num(Num::Nine) => 2,
+                NumButton::Num(Num::Zero) => 2,
+                NumButton::Accept => 1,
+            },
+            NumButton::Num(Num::Four) => match other {
+                NumButton::Num(Num::One) => 1,
+                NumButton::Num(Num::Two) => 2,
+                NumButton::Num(Num::Three) => 3,
+                NumButton::Num(Num::Four) => 0,
+                NumButton::Num(Num::Five) => 1,
+                NumButton::Num(Num::Six) => 2,
+                NumButton::Num(Num::Seven) => 1,
+                NumButton::Num(Num::Eight) => 2,
+                NumButton::Num(Num::Nine) => 3,
+                NumButton::Num(Num::Zero) => 3,
+                NumButton::Accept => 4,
+            },
+            NumButton::Num(Num::Five) => match other {
+                NumButton::Num(Num::One) => 2,
+                NumButton::Num(Num::Two) => 1,
+                NumButton::Num(Num::Three) => 2,
+                NumButton::Num(Num::Four) => 1,
+                NumButton::Num(Num::Five) => 0,
+                NumButton::Num(Num::Six) => 1,
+                NumButton::Num(Num::Seven) => 2,
+                NumButton::Num(Num::Eight) => 1,
+                NumButton::Num(Num::Nine) => 2,
+                NumButton::Num(Num::Zero) => 2,
+                NumButton::Accept => 3,
+            },
+            NumButton::Num(Num::Six) => match other {
+                NumButton::Num(Num::One) => 3,
+                NumButton::Num(Num::Two) => 2,
+                NumButton::Num(Num::Three) => 1,
+                NumButton::Num(Num::Four) => 2,
+                NumButton::Num(Num::Five) => 1,
+                NumButton::Num(Num::Six) => 0,
+                NumButton::Num(Num::Seven) => 3,
+                NumButton::Num(Num::Eight) => 2,
+                NumButton::Num(Num::Nine) => 1,
+                NumButton::Num(Num::Zero) => 3,
+                NumButton::Accept => 2,
+            },
+            NumButton::Num(Num::Seven) => match other {
+                NumButton::Num(Num::One) => 2,
+                NumButton::Num(Num::Two) => 3,
+                NumButton::Num(Num::Three) => 4,
+                NumButton::Num(Num::Four) => 1,
+                NumButton::Num(Num::Five) => 2,
+                NumButton::Num(Num::Six) => 3,
+                NumButton::Num(Num::Seven) => 0,
+                NumButton::Num(Num::Eight) => 1,
+                NumButton::Num(Num::Nine) => 2,
+                NumButton::Num(Num::Zero) => 4,
+                NumButton::Accept => 5,
+            },
+            NumButton::Num(Num::Eight) => match other {
+                NumButton::Num(Num::One) => 3,
+                NumButton::Num(Num::Two) => 2,
+                NumButton::Num(Num::Three) => 3,
+                NumButton::Num(Num::Four) => 2,
+                NumButton::Num(Num::Five) => 1,
+                NumButton::Num(Num::Six) => 2,
+                NumButton::Num(Num::Seven) => 1,
+                NumButton::Num(Num::Eight) => 0,
+                NumButton::Num(Num::Nine) => 1,
+                NumButton::Num(Num::Zero) => 3,
+                NumButton::Accept => 4,
+            },
+            NumButton::Num(Num::Nine) => match other {
+                NumButton::Num(Num::One) => 4,
+                NumButton::Num(Num::Two) => 3,
+                NumButton::Num(Num::Three) => 2,
+                NumButton::Num(Num::Four) => 3,
+                NumButton::Num(Num::Five) => 2,
+                NumButton::Num(Num::Six) => 1,
+                NumButton::Num(Num::Seven) => 2,
+                NumButton::Num(Num::Eight) => 1,
+                NumButton::Num(Num::Nine) => 0,
+                NumButton::Num(Num::Zero) => 4,
+                NumButton::Accept => 3,
+            },
+            NumButton::Num(Num::Zero) => match other {
+                NumButton::Num(Num::One) => 2,
+                NumButton::Num(Num::Two) => 1,
+                NumButton::Num(Num::Three) => 2,
+                NumButton::Num(Num::Four) => 3,
+                NumButton::Num(Num::Five) => 2,
+                NumButton::Num(Num::Six) => 3, // was 2, found by deriving from the layout
+                NumButton::Num(Num::Seven) => 4,
+                NumButton::Num(Num::Eight) => 3,
+                NumButton::Num(Num::Nine) => 4,
+                NumButton::Num(Num::Zero) => 0,
+                NumButton::Accept => 1,
+            },
+            NumButton::Accept => match other {
+                NumButton::Num(Num::One) => 3,
+                NumButton::Num(Num::Two) => 2,
+                NumButton::Num(Num::Three) => 1,
+                NumButton::Num(Num::Four) => 4,
+                NumButton::Num(Num::Five) => 3,
+                NumButton::Num(Num::Six) => 2,
+                NumButton::Num(Num::Seven) => 5,
+                NumButton::Num(Num::Eight) => 4,
+                NumButton::Num(Num::Nine) => 3,
+                NumButton::Num(Num::Zero) => 1,
+                NumButton::Accept => 0,
+            },
+        }
+    }
+
+    #[rustfmt::skip]
+    fn hand_written_dir_cost(this: &DirButton, other: &DirButton) -> KeypadDistance {
+        // note: this could be a hashmap, but that would have more paths.
+        // This is also ensured to be complete/exhaustive
+        match (this, other) {
+	        // non-move, for completeness
+            (DirButton::Accept, DirButton::Accept)
+            | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Up))
+            | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Down))
+            | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Left))
+            | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Right))
+            => 0,
+	        // up-down
+	        (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Down))
+		    | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Up))
+	        // left-down
+	        | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Down))
+	        | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Left))
+	        // right-down
+	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Down))
+	        | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Right))
+	        // a-up
+	        | (DirButton::Accept, DirButton::Dir(Dir::Up))
+	        | (DirButton::Dir(Dir::Up), DirButton::Accept)
+	        // a-right
+	        | (DirButton::Accept, DirButton::Dir(Dir::Right))
+	        | (DirButton::Dir(Dir::Right), DirButton::Accept)
+	        => 1,
+	        // left-right
+	        (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Right))
+	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Left))
+	        // left-up
+	        | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Up))
+	        | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Left))
+	        // up-right
+	        | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Right))
+	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Up))
+	        // a-down
+	        | (DirButton::Accept, DirButton::Dir(Dir::Down))
+	        | (DirButton::Dir(Dir::Down), DirButton::Accept)
+	        => 2,
+	        // a-left
+            (DirButton::Accept, DirButton::Dir(Dir::Left))
+            | (DirButton::Dir(Dir::Left), DirButton::Accept)
+            => 3,
+        }
+    }
+
+    const NUMS: [Num; 10] = [
+        Num::Zero,
+        Num::One,
+        Num::Two,
+        Num::Three,
+        Num::Four,
+        Num::Five,
+        Num::Six,
+        Num::Seven,
+        Num::Eight,
+        Num::Nine,
+    ];
+    const DIR_BUTTONS: [DirButton; 5] = [
+        DirButton::Dir(Dir::Up),
+        DirButton::Dir(Dir::Down),
+        DirButton::Dir(Dir::Left),
+        DirButton::Dir(Dir::Right),
+        DirButton::Accept,
+    ];
+
+    #[test]
+    fn derived_costs_match_hand_written() {
+        let num_buttons = NUMS
+            .map(NumButton::Num)
+            .into_iter()
+            .chain([NumButton::Accept])
+            .collect::<Vec<_>>();
+        for (a, b) in num_buttons.iter().cartesian_product(&num_buttons) {
+            assert_eq!(
+                a.a_to_b_cost(b),
+                hand_written_num_cost(a, b),
+                "{:?} -> {:?}",
+                a,
+                b
+            );
+        }
+        for (a, b) in DIR_BUTTONS.iter().cartesian_product(&DIR_BUTTONS) {
+            assert_eq!(
+                a.a_to_b_cost(b),
+                hand_written_dir_cost(a, b),
+                "{:?} -> {:?}",
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn labels_are_on_layouts() {
+        for num in NUMS {
+            assert_eq!(
+                NumButton::Num(num).label().to_digit(10),
+                Some(u32::from(&num))
+            );
+        }
+        assert_eq!(NumButton::Accept.position(), (3, 2));
+        assert_eq!(DirButton::Dir(Dir::Up).position(), (0, 1));
+        assert_eq!(DirButton::Dir(Dir::Left).position(), (1, 0));
+    }
+
+    #[test]
+    fn puzzle_moves_avoid_gap() {
+        use DirButton::Accept as A;
+        let [up, down, left, right, _] = DIR_BUTTONS;
+        let moves = |a: DirButton, b: DirButton| DirButton::layout().moves(a.label(), b.label());
+        assert_eq!(moves(A, left), [vec![down, left, left, A]]);
+        assert_eq!(moves(left, up), [vec![right, up, A]]);
+        assert_eq!(moves(A, down), [vec![down, left, A], vec![left, down, A]]);
+        assert_eq!(moves(A, A), [vec![A]]);
+        assert_eq!(
+            NumButton::layout().moves('A', '1'),
+            [vec![up, left, left, A]]
+        );
+    }
+}
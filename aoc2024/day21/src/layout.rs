@@ -0,0 +1,172 @@
+//! Keypads described by their layout, from which all moves between buttons are derived.
+//!
+//! A layout is a grid of characters, one per button, where `' '` or `'#'` (or a missing cell at
+//! the end of a row) is a gap that robot arms may never point at.
+
+use crate::common::*;
+use anyhow::anyhow;
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+
+pub const NUMERIC_LAYOUT: &str = "789\n456\n123\n 0A";
+
+pub const DIRECTIONAL_LAYOUT: &str = " ^A\n<v>";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Layout {
+    rows: Vec<Vec<Option<char>>>,
+    positions: HashMap<char, Position>,
+}
+impl Layout {
+    pub fn parse(layout: &str) -> anyhow::Result<Self> {
+        let rows = layout
+            .lines()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        ' ' | '#' => None,
+                        label => Some(label),
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let mut positions = HashMap::new();
+        for (row, labels) in rows.iter().enumerate() {
+            for (column, label) in labels.iter().enumerate() {
+                let Some(label) = *label else {
+                    continue;
+                };
+                let position = (row as i8, column as i8);
+                if positions.insert(label, position).is_some() {
+                    return Err(anyhow!("button {} appears more than once", label));
+                }
+            }
+        }
+        if !positions.contains_key(&'A') {
+            return Err(anyhow!("robots start at A, which is missing"));
+        }
+        Ok(Layout { rows, positions })
+    }
+
+    pub fn position(&self, label: char) -> Option<Position> {
+        self.positions.get(&label).copied()
+    }
+
+    /// Button at some position, or `None` for gaps and positions outside the keypad.
+    pub fn label(&self, (row, column): Position) -> Option<char> {
+        let row = self.rows.get(usize::try_from(row).ok()?)?;
+        *row.get(usize::try_from(column).ok()?)?
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = char> + '_ {
+        self.positions.keys().copied()
+    }
+
+    /// All shortest ways to move from one button to another and press it, which turn the fewest
+    /// times: repeating a direction is free for the keypads above (pressing `A` again),
+    /// so turning more than needed never helps.
+    /// On the puzzle keypads, these are the (at most two) L shapes not passing the gap.
+    pub fn moves(&self, from: char, to: char) -> Vec<Vec<DirButton>> {
+        let (Some(from), Some(to)) = (self.position(from), self.position(to)) else {
+            return vec![];
+        };
+        // breadth-first distances to `to`, so the depth-first search below only walks shortest paths
+        let mut distances = HashMap::from([(to, 0)]);
+        let mut queue = VecDeque::from([to]);
+        while let Some(position) = queue.pop_front() {
+            for (_, next) in self.neighbours(position) {
+                if !distances.contains_key(&next) {
+                    distances.insert(next, distances[&position] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        fn walk(
+            layout: &Layout,
+            distances: &HashMap<Position, usize>,
+            position: Position,
+            path: &mut Vec<DirButton>,
+            paths: &mut Vec<Vec<DirButton>>,
+        ) {
+            if distances[&position] == 0 {
+                paths.push([&path[..], &[DirButton::Accept]].concat());
+                return;
+            }
+            for (direction, next) in layout.neighbours(position) {
+                if distances.get(&next) == Some(&(distances[&position] - 1)) {
+                    path.push(direction);
+                    walk(layout, distances, next, path, paths);
+                    path.pop();
+                }
+            }
+        }
+        let mut paths = vec![];
+        if distances.contains_key(&from) {
+            walk(self, &distances, from, &mut vec![], &mut paths);
+        }
+        let turns = |path: &Vec<DirButton>| path.iter().dedup().count();
+        let fewest = paths.iter().map(turns).min().unwrap_or_default();
+        paths.retain(|path| turns(path) == fewest);
+        paths
+    }
+
+    fn neighbours(
+        &self,
+        (row, column): Position,
+    ) -> impl Iterator<Item = (DirButton, Position)> + '_ {
+        [
+            (DirButton::Dir(Dir::Up), (row - 1, column)),
+            (DirButton::Dir(Dir::Down), (row + 1, column)),
+            (DirButton::Dir(Dir::Left), (row, column - 1)),
+            (DirButton::Dir(Dir::Right), (row, column + 1)),
+        ]
+        .into_iter()
+        .filter(|&(_, position)| self.label(position).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_layouts_refused() {
+        assert!(Layout::parse("12\n3A").is_ok());
+        assert!(Layout::parse("1A\nA2").is_err());
+        assert!(Layout::parse("123").is_err());
+    }
+
+    #[test]
+    fn gaps_in_layouts() {
+        let layout = Layout::parse("#^A\n<v>").expect("valid");
+        assert_eq!(layout, Layout::parse(DIRECTIONAL_LAYOUT).expect("valid"));
+        assert_eq!(layout.label((0, 0)), None);
+        assert_eq!(layout.label((5, 0)), None);
+        assert_eq!(layout.label((1, 2)), Some('>'));
+    }
+
+    #[test]
+    fn moves_go_around_gaps() {
+        use DirButton::Accept as A;
+        let (up, down, left, right) = (
+            DirButton::Dir(Dir::Up),
+            DirButton::Dir(Dir::Down),
+            DirButton::Dir(Dir::Left),
+            DirButton::Dir(Dir::Right),
+        );
+        // no L shape possible, both corners are gaps
+        let layout = Layout::parse("12\n#A\n34").expect("valid");
+        assert_eq!(layout.moves('1', '3'), [vec![right, down, down, left, A]]);
+        assert_eq!(layout.moves('1', 'B'), vec![] as Vec<Vec<DirButton>>);
+        let layout = Layout::parse("123\n4#5\n6A7").expect("valid");
+        assert_eq!(
+            layout.moves('4', '5'),
+            [
+                vec![up, right, right, down, A],
+                vec![down, right, right, up, A]
+            ]
+        );
+        assert_eq!(layout.moves('A', 'A'), [vec![A]]);
+    }
+}
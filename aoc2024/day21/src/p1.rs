@@ -1,7 +1,11 @@
 #![doc = include_str!("../p1.md")]
 
-use itertools::Itertools;
-use std::fmt::{Display, Formatter};
+mod chain;
+mod common;
+mod layout;
+
+use chain::*;
+use common::*;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -14,7 +18,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<21>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
@@ -107,343 +111,8 @@ fn solve(input: impl AsRef<str>) -> u64 {
     let codes = parse_sequences
         .parse_next(&mut input.as_ref())
         .expect("parsable");
-    codes
-        .into_iter()
-        .map(|code| {
-            let sequence = code.generate_optimal_sequence();
-            sequence.0.len() as u64 * code.to_number() as u64
-        })
-        .sum()
-}
-
-struct Sequence(Vec<DirButton>);
-impl Display for Sequence {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.iter().join(""))
-    }
-}
-
-/// I assume all codes are three digits long?
-/// It is that long for my input and for the examples.
-struct Code([Num; 3]);
-impl Code {
-    fn generate_optimal_sequence(&self) -> Sequence {
-        todo!()
-    }
-    fn to_number(&self) -> u32 {
-        self.0
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(i, val)| 10u32.pow(i as u32) * u32::from(val))
-            .sum()
-    }
-}
-impl Display for Code {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}A: {}",
-            self.0.iter().join(""),
-            self.generate_optimal_sequence()
-        )
-    }
-}
-
-fn parse_sequences(input: &mut &str) -> PResult<Vec<Code>> {
-    separated(1.., parse_sequence, ('A', line_ending)).parse_next(input)
-}
-fn parse_sequence(input: &mut &str) -> PResult<Code> {
-    (parse_num, parse_num, parse_num)
-        .map(|(a, b, c)| Code([a, b, c]))
-        .parse_next(input)
-}
-fn parse_num(input: &mut &str) -> PResult<Num> {
-    let mut num_parser = dispatch! {any;
-        '1' => empty.value(Num::One),
-        '2' => empty.value(Num::Two),
-        '3' => empty.value(Num::Three),
-        '4' => empty.value(Num::Four),
-        '5' => empty.value(Num::Five),
-        '6' => empty.value(Num::Six),
-        '7' => empty.value(Num::Seven),
-        '8' => empty.value(Num::Eight),
-        '9' => empty.value(Num::Nine),
-        '0' => empty.value(Num::Zero),
-        _ => fail,
-    };
-    num_parser.parse_next(input)
-}
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Num {
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Zero,
-}
-impl Display for Num {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", u32::from(self))
-    }
-}
-impl From<&Num> for u32 {
-    fn from(value: &Num) -> Self {
-        match value {
-            Num::One => 1,
-            Num::Two => 2,
-            Num::Three => 3,
-            Num::Four => 4,
-            Num::Five => 5,
-            Num::Six => 6,
-            Num::Seven => 7,
-            Num::Eight => 8,
-            Num::Nine => 9,
-            Num::Zero => 0,
-        }
-    }
-}
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum NumButton {
-    Num(Num),
-    Accept,
-}
-impl TravelCost for NumButton {
-    fn a_to_b_cost(&self, other: &Self) -> KeypadDistance {
-        // this could be generated using some simple inductive rules
-        match self {
-            NumButton::Num(Num::One) => match other {
-                NumButton::Num(Num::One) => 0,
-                NumButton::Num(Num::Two) => 1,
-                NumButton::Num(Num::Three) => 2,
-                NumButton::Num(Num::Four) => 1,
-                NumButton::Num(Num::Five) => 2,
-                NumButton::Num(Num::Six) => 3,
-                NumButton::Num(Num::Seven) => 2,
-                NumButton::Num(Num::Eight) => 3,
-                NumButton::Num(Num::Nine) => 4,
-                NumButton::Num(Num::Zero) => 2,
-                NumButton::Accept => 3,
-            },
-            NumButton::Num(Num::Two) => match other {
-                NumButton::Num(Num::One) => 1,
-                NumButton::Num(Num::Two) => 0,
-                NumButton::Num(Num::Three) => 1,
-                NumButton::Num(Num::Four) => 2,
-                NumButton::Num(Num::Five) => 1,
-                NumButton::Num(Num::Six) => 2,
-                NumButton::Num(Num::Seven) => 3,
-                NumButton::Num(Num::Eight) => 2,
-                NumButton::Num(Num::Nine) => 3,
-                NumButton::Num(Num::Zero) => 1,
-                NumButton::Accept => 2,
-            },
-            NumButton::Num(Num::Three) => match other {
-                NumButton::Num(Num::One) => 2,
-                NumButton::Num(Num::Two) => 1,
-                NumButton::Num(Num::Three) => 0,
-                NumButton::Num(Num::Four) => 3,
-                NumButton::Num(Num::Five) => 2,
-                NumButton::Num(Num::Six) => 1,
-                NumButton::Num(Num::Seven) => 4,
-                NumButton::Num(Num::Eight) => 3,
-                NumButton::Num(Num::Nine) => 2,
-                NumButton::Num(Num::Zero) => 2,
-                NumButton::Accept => 1,
-            },
-            NumButton::Num(Num::Four) => match other {
-                NumButton::Num(Num::One) => 1,
-                NumButton::Num(Num::Two) => 2,
-                NumButton::Num(Num::Three) => 3,
-                NumButton::Num(Num::Four) => 0,
-                NumButton::Num(Num::Five) => 1,
-                NumButton::Num(Num::Six) => 2,
-                NumButton::Num(Num::Seven) => 1,
-                NumButton::Num(Num::Eight) => 2,
-                NumButton::Num(Num::Nine) => 3,
-                NumButton::Num(Num::Zero) => 3,
-                NumButton::Accept => 4,
-            },
-            NumButton::Num(Num::Five) => match other {
-                NumButton::Num(Num::One) => 2,
-                NumButton::Num(Num::Two) => 1,
-                NumButton::Num(Num::Three) => 2,
-                NumButton::Num(Num::Four) => 1,
-                NumButton::Num(Num::Five) => 0,
-                NumButton::Num(Num::Six) => 1,
-                NumButton::Num(Num::Seven) => 2,
-                NumButton::Num(Num::Eight) => 1,
-                NumButton::Num(Num::Nine) => 2,
-                NumButton::Num(Num::Zero) => 2,
-                NumButton::Accept => 3,
-            },
-            NumButton::Num(Num::Six) => match other {
-                NumButton::Num(Num::One) => 3,
-                NumButton::Num(Num::Two) => 2,
-                NumButton::Num(Num::Three) => 1,
-                NumButton::Num(Num::Four) => 2,
-                NumButton::Num(Num::Five) => 1,
-                NumButton::Num(Num::Six) => 0,
-                NumButton::Num(Num::Seven) => 3,
-                NumButton::Num(Num::Eight) => 2,
-                NumButton::Num(Num::Nine) => 1,
-                NumButton::Num(Num::Zero) => 3,
-                NumButton::Accept => 2,
-            },
-            NumButton::Num(Num::Seven) => match other {
-                NumButton::Num(Num::One) => 2,
-                NumButton::Num(Num::Two) => 3,
-                NumButton::Num(Num::Three) => 4,
-                NumButton::Num(Num::Four) => 1,
-                NumButton::Num(Num::Five) => 2,
-                NumButton::Num(Num::Six) => 3,
-                NumButton::Num(Num::Seven) => 0,
-                NumButton::Num(Num::Eight) => 1,
-                NumButton::Num(Num::Nine) => 2,
-                NumButton::Num(Num::Zero) => 4,
-                NumButton::Accept => 5,
-            },
-            NumButton::Num(Num::Eight) => match other {
-                NumButton::Num(Num::One) => 3,
-                NumButton::Num(Num::Two) => 2,
-                NumButton::Num(Num::Three) => 3,
-                NumButton::Num(Num::Four) => 2,
-                NumButton::Num(Num::Five) => 1,
-                NumButton::Num(Num::Six) => 2,
-                NumButton::Num(Num::Seven) => 1,
-                NumButton::Num(Num::Eight) => 0,
-                NumButton::Num(Num::Nine) => 1,
-                NumButton::Num(Num::Zero) => 3,
-                NumButton::Accept => 4,
-            },
-            NumButton::Num(Num::Nine) => match other {
-                NumButton::Num(Num::One) => 4,
-                NumButton::Num(Num::Two) => 3,
-                NumButton::Num(Num::Three) => 2,
-                NumButton::Num(Num::Four) => 3,
-                NumButton::Num(Num::Five) => 2,
-                NumButton::Num(Num::Six) => 1,
-                NumButton::Num(Num::Seven) => 2,
-                NumButton::Num(Num::Eight) => 1,
-                NumButton::Num(Num::Nine) => 0,
-                NumButton::Num(Num::Zero) => 4,
-                NumButton::Accept => 3,
-            },
-            NumButton::Num(Num::Zero) => match other {
-                NumButton::Num(Num::One) => 2,
-                NumButton::Num(Num::Two) => 1,
-                NumButton::Num(Num::Three) => 2,
-                NumButton::Num(Num::Four) => 3,
-                NumButton::Num(Num::Five) => 2,
-                NumButton::Num(Num::Six) => 2,
-                NumButton::Num(Num::Seven) => 4,
-                NumButton::Num(Num::Eight) => 3,
-                NumButton::Num(Num::Nine) => 4,
-                NumButton::Num(Num::Zero) => 0,
-                NumButton::Accept => 1,
-            },
-            NumButton::Accept => match other {
-                NumButton::Num(Num::One) => 3,
-                NumButton::Num(Num::Two) => 2,
-                NumButton::Num(Num::Three) => 1,
-                NumButton::Num(Num::Four) => 4,
-                NumButton::Num(Num::Five) => 3,
-                NumButton::Num(Num::Six) => 2,
-                NumButton::Num(Num::Seven) => 5,
-                NumButton::Num(Num::Eight) => 4,
-                NumButton::Num(Num::Nine) => 3,
-                NumButton::Num(Num::Zero) => 1,
-                NumButton::Accept => 0,
-            },
-        }
-    }
-}
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum DirButton {
-    Dir(Dir),
-    Accept,
-}
-impl Display for DirButton {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                DirButton::Dir(Dir::Up) => '^',
-                DirButton::Dir(Dir::Down) => 'v',
-                DirButton::Dir(Dir::Left) => '<',
-                DirButton::Dir(Dir::Right) => '>',
-                DirButton::Accept => 'A',
-            }
-        )
-    }
-}
-impl TravelCost for DirButton {
-    fn a_to_b_cost(&self, other: &Self) -> KeypadDistance {
-        // note: this could be a hashmap, but that would have more paths.
-        // This is also ensured to be complete/exhaustive
-        match (self, other) {
-	        // non-move, for completeness
-            (DirButton::Accept, DirButton::Accept)
-            | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Up))
-            | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Down))
-            | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Left))
-            | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Right))
-            => 0,
-	        // up-down
-	        (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Down))
-		    | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Up))
-	        // left-down
-	        | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Down))
-	        | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Left))
-	        // right-down
-	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Down))
-	        | (DirButton::Dir(Dir::Down), DirButton::Dir(Dir::Right))
-	        // a-up
-	        | (DirButton::Accept, DirButton::Dir(Dir::Up))
-	        | (DirButton::Dir(Dir::Up), DirButton::Accept)
-	        // a-right
-	        | (DirButton::Accept, DirButton::Dir(Dir::Right))
-	        | (DirButton::Dir(Dir::Right), DirButton::Accept)
-	        => 1,
-	        // left-right
-	        (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Right))
-	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Left))
-	        // left-up
-	        | (DirButton::Dir(Dir::Left), DirButton::Dir(Dir::Up))
-	        | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Left))
-	        // up-right
-	        | (DirButton::Dir(Dir::Up), DirButton::Dir(Dir::Right))
-	        | (DirButton::Dir(Dir::Right), DirButton::Dir(Dir::Up))
-	        // a-down
-	        | (DirButton::Accept, DirButton::Dir(Dir::Down))
-	        | (DirButton::Dir(Dir::Down), DirButton::Accept)
-	        => 2,
-	        // a-left
-            (DirButton::Accept, DirButton::Dir(Dir::Left))
-            | (DirButton::Dir(Dir::Left), DirButton::Accept)
-            => 3,
-        }
-    }
-}
-
-type KeypadDistance = u8;
-trait TravelCost {
-    fn a_to_b_cost(&self, other: &Self) -> KeypadDistance;
+    let mut chain = KeypadChain::new(2);
+    codes.iter().map(|code| chain.complexity(code)).sum()
 }
 
 #[cfg(test)]
@@ -1,5 +1,11 @@
 //#![doc = include_str!("../p2.md")]
 
+mod chain;
+mod common;
+mod layout;
+
+use chain::*;
+use common::*;
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -12,23 +18,33 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<21>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
+/// # Problem
+/// Same as before, but with 25 robots on directional keypads instead of two.
+/// # Solution
+/// Sequences get far too long to generate, but the cost of every move on a directional keypad
+/// only depends on the buttons and the amount of keypads above it,
+/// so it is the same search as in part one with more layers (see [`KeypadChain`]).
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    let codes = parse_sequences
+        .parse_next(&mut input.as_ref())
+        .expect("parsable");
+    let mut chain = KeypadChain::new(25);
+    codes.iter().map(|code| chain.complexity(code)).sum()
 }
 
 #[cfg(test)]
 mod tests {
     #[test]
     fn example_solvable() {
-        assert_eq!(super::solve(include_str!("EXAMPLE")), todo!());
+        assert_eq!(super::solve(include_str!("EXAMPLE")), 154115708116294);
     }
 
     #[ignore]
     #[test]
     fn input_solvable() {
-        assert_eq!(super::solve(include_str!("../../inputs/21")), todo!());
+        assert_eq!(super::solve(include_str!("../../inputs/21")), 0);
     }
 }
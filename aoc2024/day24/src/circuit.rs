@@ -0,0 +1,287 @@
+//! Compiled form of a device, for simulating it many times.
+//!
+//! [`descend_graph_cached`] searches the source of every wire through all connections, and never
+//! terminates for cyclical graphs (which swapping outputs can easily create).
+//! Compiling interns every wire to an index and sorts the gates topologically once, refusing
+//! cyclical devices, so that evaluating is a single pass over the gates.
+//! Every wire holds a `u64` instead of a `bool`, evaluating 64 inputs at once (one per bit lane).
+
+use crate::common::*;
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+
+pub const LANES: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CircuitError<'s> {
+    /// Outputs of the gates on a cycle, or depending on one.
+    Cycle(Vec<WireName<'s>>),
+    /// Wire that is neither an input nor the output of some gate.
+    Undriven(WireName<'s>),
+    /// More `x`, `y` or `z` wires than bits of a `u64`.
+    TooWide { bits: usize },
+}
+impl Display for CircuitError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::Cycle(wires) => write!(
+                f,
+                "cyclical, no order for {}",
+                wires.iter().map(|w| w.0).join(", ")
+            ),
+            CircuitError::Undriven(wire) => write!(f, "nothing drives {}", wire.0),
+            CircuitError::TooWide { bits } => {
+                write!(f, "{} bits wide, at most {} fit", bits, u64::BITS)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct CompiledGate {
+    a: usize,
+    b: usize,
+    gate: Gate,
+    dest: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Circuit<'s> {
+    names: Vec<WireName<'s>>,
+    /// In topological order, inputs before outputs.
+    gates: Vec<CompiledGate>,
+    /// Indices of `xNN`, `yNN` and `zNN` wires, in order of their bits.
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+}
+impl<'s> Circuit<'s> {
+    pub fn compile(
+        states: &States<'s>,
+        connections: &Connections<'s>,
+    ) -> Result<Self, CircuitError<'s>> {
+        let names = states
+            .keys()
+            .copied()
+            .chain(connections.iter().map(|c| c.dest))
+            .unique()
+            .sorted_by_key(|w| w.0)
+            .collect_vec();
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(id, w)| (*w, id))
+            .collect::<HashMap<_, _>>();
+        let id = |wire: &WireName<'s>| ids.get(wire).copied().ok_or(CircuitError::Undriven(*wire));
+        let unsorted = connections
+            .iter()
+            .map(|c| {
+                Ok(CompiledGate {
+                    a: id(&c.a)?,
+                    b: id(&c.b)?,
+                    gate: c.gate,
+                    dest: id(&c.dest)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Kahn's algorithm: a gate is ready once both of its inputs are known
+        let mut readers = vec![vec![]; names.len()];
+        let mut missing_inputs = vec![2u8; unsorted.len()];
+        for (index, gate) in unsorted.iter().enumerate() {
+            readers[gate.a].push(index);
+            readers[gate.b].push(index);
+        }
+        let mut known = states.keys().map(|w| ids[w]).collect::<VecDeque<_>>();
+        let mut gates = Vec::with_capacity(unsorted.len());
+        while let Some(wire) = known.pop_front() {
+            for &reader in &readers[wire] {
+                missing_inputs[reader] -= 1;
+                if missing_inputs[reader] == 0 {
+                    gates.push(unsorted[reader]);
+                    known.push_back(unsorted[reader].dest);
+                }
+            }
+        }
+        if gates.len() < unsorted.len() {
+            let stuck = unsorted
+                .iter()
+                .zip(missing_inputs)
+                .filter(|&(_, missing)| missing > 0)
+                .map(|(gate, _)| names[gate.dest])
+                .sorted_by_key(|w| w.0)
+                .collect();
+            return Err(CircuitError::Cycle(stuck));
+        }
+
+        let numbered = |prefix| {
+            names
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| w.0.starts_with(prefix))
+                .map(|(id, _)| id)
+                .collect_vec()
+        };
+        let (x, y, z) = (numbered('x'), numbered('y'), numbered('z'));
+        let bits = x.len().max(y.len()).max(z.len());
+        if bits > u64::BITS as usize {
+            return Err(CircuitError::TooWide { bits });
+        }
+        Ok(Circuit {
+            x,
+            y,
+            z,
+            names,
+            gates,
+        })
+    }
+
+    pub fn input_bits(&self) -> usize {
+        self.x.len()
+    }
+
+    /// Value of every wire, for up to 64 inputs at once: bit `l` of a wire belongs to input `l`.
+    fn evaluate_lanes(&self, x: impl Fn(usize) -> u64, y: impl Fn(usize) -> u64) -> Vec<u64> {
+        let mut values = vec![0; self.names.len()];
+        for (bit, &id) in self.x.iter().enumerate() {
+            values[id] = x(bit);
+        }
+        for (bit, &id) in self.y.iter().enumerate() {
+            values[id] = y(bit);
+        }
+        for gate in &self.gates {
+            let (a, b) = (values[gate.a], values[gate.b]);
+            values[gate.dest] = match gate.gate {
+                Gate::And => a & b,
+                Gate::Or => a | b,
+                Gate::Xor => a ^ b,
+            };
+        }
+        values
+    }
+
+    /// Outputs for every pair of `xs` and `ys`, [`LANES`] pairs per pass over the gates.
+    pub fn outputs(&self, xs: &[u64], ys: &[u64]) -> Vec<u64> {
+        assert_eq!(xs.len(), ys.len(), "inputs come in pairs");
+        xs.chunks(LANES)
+            .zip(ys.chunks(LANES))
+            .flat_map(|(xs, ys)| {
+                // transpose: bit of each input into the lane of that input
+                let lanes = |inputs: &[u64], bit: usize| {
+                    inputs
+                        .iter()
+                        .enumerate()
+                        .fold(0, |lanes, (lane, input)| lanes | (input >> bit & 1) << lane)
+                };
+                let values = self.evaluate_lanes(|bit| lanes(xs, bit), |bit| lanes(ys, bit));
+                (0..xs.len()).map(move |lane| {
+                    self.z
+                        .iter()
+                        .enumerate()
+                        .fold(0, |z, (bit, &id)| z | (values[id] >> lane & 1) << bit)
+                })
+            })
+            .collect()
+    }
+
+    /// Output for a single pair of inputs.
+    #[cfg(test)]
+    pub fn output(&self, x: u64, y: u64) -> u64 {
+        self.outputs(&[x], &[y])[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::Parser;
+
+    fn parsed(input: &str) -> (States<'_>, Connections<'_>) {
+        parse_device.parse_next(&mut &*input).expect("parsable")
+    }
+
+    /// Adder of 4 bit, like the ones in actual inputs.
+    const ADDER: &str = "\
+x00: 0\nx01: 0\nx02: 0\nx03: 0\ny00: 0\ny01: 0\ny02: 0\ny03: 0
+
+x00 XOR y00 -> z00\nx00 AND y00 -> c00
+x01 XOR y01 -> p01\nx01 AND y01 -> g01\np01 XOR c00 -> z01\np01 AND c00 -> t01\ng01 OR t01 -> c01
+x02 XOR y02 -> p02\nx02 AND y02 -> g02\np02 XOR c01 -> z02\np02 AND c01 -> t02\ng02 OR t02 -> c02
+x03 XOR y03 -> p03\nx03 AND y03 -> g03\np03 XOR c02 -> z03\np03 AND c02 -> t03\ng03 OR t03 -> z04";
+
+    #[test]
+    fn adder_adds_everything() {
+        let (states, connections) = parsed(ADDER);
+        let circuit = Circuit::compile(&states, &connections).expect("acyclic");
+        let (xs, ys): (Vec<u64>, Vec<u64>) = (0..16).cartesian_product(0..16).unzip();
+        let sums = xs.iter().zip(&ys).map(|(x, y)| x + y).collect_vec();
+        // more than one pass of lanes
+        assert_eq!(circuit.outputs(&xs, &ys), sums);
+        assert_eq!(circuit.output(15, 15), 30);
+    }
+
+    #[test]
+    fn matches_descending_graph() {
+        let (states, connections) = parsed(include_str!("EXAMPLE_LARGER"));
+        let circuit = Circuit::compile(&states, &connections).expect("acyclic");
+        let value = |prefix| {
+            states
+                .iter()
+                .filter(|(w, &v)| w.0.starts_with(prefix) && v)
+                .map(|(w, _)| 1 << w.0[1..].parse::<u64>().unwrap())
+                .sum::<u64>()
+        };
+        let mut known = states.clone();
+        let expected = connections
+            .iter()
+            .filter(|c| c.dest.0.starts_with('z'))
+            .map(|c| {
+                let bit = c.dest.0[1..].parse::<u64>().unwrap();
+                (descend_graph_cached(&c.dest, &connections, &mut known) as u64) << bit
+            })
+            .sum::<u64>();
+        assert_eq!(circuit.output(value('x'), value('y')), expected);
+        assert_eq!(expected, 2024);
+    }
+
+    #[test]
+    fn cycles_refused() {
+        // swapping the outputs of a sum and its carry feeds the carry into itself
+        let swapped = ADDER
+            .replace("p02 XOR c01 -> z02", "p02 XOR c01 -> c01")
+            .replace("g01 OR t01 -> c01", "g01 OR t01 -> z02");
+        let (states, connections) = parsed(&swapped);
+        assert_eq!(
+            Circuit::compile(&states, &connections).map(|_| ()),
+            Err(CircuitError::Cycle(
+                ["c01", "c02", "t02", "t03", "z03", "z04"]
+                    .map(WireName)
+                    .to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn too_wide_refused() {
+        let outputs = (0..=64)
+            .map(|bit| format!("x00 AND y00 -> z{:02}", bit))
+            .join("\n");
+        let wide = format!("x00: 1\ny00: 1\n\n{}", outputs);
+        let (states, connections) = parsed(&wide);
+        assert_eq!(
+            Circuit::compile(&states, &connections).map(|_| ()),
+            Err(CircuitError::TooWide { bits: 65 })
+        );
+    }
+
+    #[test]
+    fn undriven_refused() {
+        let undriven = ADDER.replace("p01 XOR c00", "p01 XOR abc");
+        let (states, connections) = parsed(&undriven);
+        assert_eq!(
+            Circuit::compile(&states, &connections).map(|_| ()),
+            Err(CircuitError::Undriven(WireName("abc")))
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -90,6 +91,15 @@ pub struct Connection<'s> {
     pub gate: Gate,
     pub dest: WireName<'s>,
 }
+impl Display for Connection<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} -> {}",
+            self.a.0, self.gate, self.b.0, self.dest.0
+        )
+    }
+}
 
 fn parse_wirename<'s>(input: &mut &'s str) -> PResult<WireName<'s>> {
     take(3usize).map(WireName).parse_next(input)
@@ -113,3 +123,12 @@ pub enum Gate {
     Or,
     Xor,
 }
+impl Display for Gate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Gate::And => write!(f, "AND"),
+            Gate::Or => write!(f, "OR"),
+            Gate::Xor => write!(f, "XOR"),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Exports the device of a puzzle input as a Graphviz DOT graph to stdout.
+//!
+//! `cargo run --bin dot -- [input] [--values] [--x=N] [--y=N] [--highlight] > device.dot`:
+//! `--values` labels every wire with its value for the initial states of the input,
+//! `--x=N` and `--y=N` label them for those inputs instead,
+//! `--highlight` marks the gates whose outputs were found to be swapped (see `verifier`).
+
+mod circuit;
+mod common;
+mod graphviz;
+mod verifier;
+
+use common::*;
+use graphviz::*;
+use verifier::*;
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    prelude::*,
+    stream::*,
+    token::*,
+    {PResult, Parser},
+};
+
+/// Input value from e.g. `--x=N`, if given.
+fn input_from_args(input: &str) -> Option<u64> {
+    util::flag_value(input).map(|value| value.parse().expect("inputs formatted like `--x=13`"))
+}
+
+fn main() {
+    let input = util::day_input!();
+    let (states, connections) = parse_device
+        .parse_next(&mut input.as_ref())
+        .expect("parsable");
+
+    let mut options = DotOptions::default();
+    let (x, y) = (input_from_args("x"), input_from_args("y"));
+    if util::has_flag("values") || x.is_some() || y.is_some() {
+        options.values = Some(evaluate(&with_inputs(&states, x, y), &connections));
+    }
+    if util::has_flag("highlight") {
+        match find_swaps(Device::of(&connections), &states, &connections) {
+            Ok(swaps) => {
+                for swap in swaps {
+                    eprintln!("{}", swap);
+                    options
+                        .highlighted
+                        .extend([swap.broken.dest, swap.other.dest]);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    print!("{}", to_dot(&states, &connections, &options));
+}
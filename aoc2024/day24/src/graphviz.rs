@@ -0,0 +1,205 @@
+//! Export of a device as a Graphviz DOT graph, e.g. `dot -Tsvg device.dot > device.svg`.
+//!
+//! Every gate is a node named after its output wire (every wire has exactly one source),
+//! coloured by its kind. Inputs of the same bit share a rank, and both inputs and outputs are
+//! ranked below the ones of the previous bit, so the adder reads from top to bottom in order of its bits.
+
+use crate::common::*;
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+#[derive(Debug, Default, Clone)]
+pub struct DotOptions<'s> {
+    /// Outputs of gates to highlight, e.g. the ones found to be swapped.
+    pub highlighted: HashSet<WireName<'s>>,
+    /// Value of every wire, to label nodes and edges with.
+    pub values: Option<States<'s>>,
+}
+
+fn fill_color(gate: Gate) -> &'static str {
+    match gate {
+        Gate::And => "lightblue",
+        Gate::Or => "palegreen",
+        Gate::Xor => "gold",
+    }
+}
+
+/// Initial states with the bits of `x` and `y` on their input wires, where given.
+pub fn with_inputs<'s>(states: &States<'s>, x: Option<u64>, y: Option<u64>) -> States<'s> {
+    let mut states = states.clone();
+    for (wire, state) in states.iter_mut() {
+        let value = match &wire.0[..1] {
+            "x" => x,
+            "y" => y,
+            _ => None,
+        };
+        if let Some(value) = value {
+            let bit = wire.0[1..].parse::<u32>().expect("inputs end in their bit");
+            *state = value.checked_shr(bit).unwrap_or(0) & 1 == 1;
+        }
+    }
+    states
+}
+
+/// Value of every wire of the device, starting from `states`.
+pub fn evaluate<'s>(states: &States<'s>, connections: &'s Connections<'s>) -> States<'s> {
+    let mut known = states.clone();
+    for connection in connections {
+        descend_graph_cached(&connection.dest, connections, &mut known);
+    }
+    known
+}
+
+pub fn to_dot(states: &States, connections: &Connections, options: &DotOptions) -> String {
+    let value = |wire: &WireName| {
+        options
+            .values
+            .as_ref()
+            .and_then(|values| values.get(wire))
+            .map(|&v| v as u8)
+    };
+    let label = |wire: &WireName, kind: &str| match value(wire) {
+        Some(v) => format!("{}\\n{} = {}", kind, wire.0, v),
+        None => format!("{}\\n{}", kind, wire.0),
+    };
+    let highlight = |wire: &WireName| match options.highlighted.contains(wire) {
+        true => ", color = red, penwidth = 3",
+        false => "",
+    };
+
+    let mut dot = String::from("digraph device {\n    node [style = filled];\n");
+    for input in states.keys().sorted_by_key(|w| w.0) {
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [shape = box, fillcolor = white, label = \"{}\"{}];",
+            input.0,
+            label(input, "input"),
+            highlight(input)
+        );
+    }
+    for connection in connections.iter().sorted_by_key(|c| c.dest.0) {
+        let dest = &connection.dest;
+        let shape = match dest.0.starts_with('z') {
+            true => "doubleoctagon",
+            false => "ellipse",
+        };
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [shape = {}, fillcolor = {}, label = \"{}\"{}];",
+            dest.0,
+            shape,
+            fill_color(connection.gate),
+            label(dest, &connection.gate.to_string()),
+            highlight(dest)
+        );
+        for input in [&connection.a, &connection.b] {
+            let edge_label = match value(input) {
+                Some(v) => format!(" [label = \"{}\"]", v),
+                None => String::new(),
+            };
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\"{};", input.0, dest.0, edge_label);
+        }
+    }
+
+    // ranks: x and y of the same bit next to each other
+    let inputs_by_bit = states
+        .keys()
+        .into_group_map_by(|w| &w.0[1..])
+        .into_iter()
+        .sorted_by_key(|&(bit, _)| bit)
+        .map(|(_, inputs)| inputs.into_iter().sorted_by_key(|w| w.0).collect_vec())
+        .collect_vec();
+    for inputs in &inputs_by_bit {
+        let _ = writeln!(
+            dot,
+            "    {{ rank = same; {} }}",
+            inputs.iter().map(|w| format!("\"{}\";", w.0)).join(" ")
+        );
+    }
+    // invisible edges rank every bit below the previous one, for the inputs and the outputs
+    let outputs = connections
+        .iter()
+        .map(|c| c.dest)
+        .filter(|w| w.0.starts_with('z'))
+        .sorted_by_key(|w| w.0)
+        .collect_vec();
+    for wires in [
+        inputs_by_bit.iter().map(|inputs| *inputs[0]).collect_vec(),
+        outputs,
+    ] {
+        if wires.len() > 1 {
+            let _ = writeln!(
+                dot,
+                "    {} [style = invis];",
+                wires.iter().map(|w| format!("\"{}\"", w.0)).join(" -> ")
+            );
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::Parser;
+
+    #[test]
+    fn example_exports() {
+        let (states, connections) = parse_device
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let dot = to_dot(&states, &connections, &DotOptions::default());
+        assert!(dot.starts_with("digraph device {"));
+        assert!(dot
+            .contains("\"z01\" [shape = doubleoctagon, fillcolor = gold, label = \"XOR\\nz01\"];"));
+        assert!(dot.contains("\"x01\" -> \"z01\";"));
+        assert!(dot.contains("{ rank = same; \"x02\"; \"y02\"; }"));
+        assert!(dot.contains("\"x00\" -> \"x01\" -> \"x02\" [style = invis];"));
+        assert!(dot.contains("\"z00\" -> \"z01\" -> \"z02\" [style = invis];"));
+        assert!(!dot.contains("rank = sink"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn values_and_highlights_annotated() {
+        let (states, connections) = parse_device
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let options = DotOptions {
+            highlighted: HashSet::from([WireName("z02")]),
+            values: Some(evaluate(&states, &connections)),
+        };
+        let dot = to_dot(&states, &connections, &options);
+        assert!(dot.contains(
+            "\"z02\" [shape = doubleoctagon, fillcolor = palegreen, label = \"OR\\nz02 = 1\", color = red, penwidth = 3];"
+        ));
+        assert!(dot.contains("\"x00\" -> \"z00\" [label = \"1\"];"));
+        assert!(dot.contains("\"y00\" -> \"z00\" [label = \"0\"];"));
+        assert!(dot.contains("label = \"AND\\nz00 = 0\""));
+    }
+
+    #[test]
+    fn inputs_replaced_by_bit() {
+        let (states, connections) = parse_device
+            .parse_next(&mut include_str!("EXAMPLE"))
+            .expect("parsable");
+        let values = evaluate(&with_inputs(&states, Some(0b110), None), &connections);
+        let value = |wire| values[&WireName(wire)];
+        assert_eq!(
+            [value("x00"), value("x01"), value("x02")],
+            [false, true, true]
+        );
+        // `y` keeps the initial states of the input
+        assert_eq!(
+            [value("y00"), value("y01"), value("y02")],
+            [false, true, false]
+        );
+        // bit 0 is ANDed, bit 1 XORed and bit 2 ORed
+        assert_eq!(
+            [value("z00"), value("z01"), value("z02")],
+            [false, false, true]
+        );
+    }
+}
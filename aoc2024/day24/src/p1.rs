@@ -7,7 +7,7 @@ use winnow::Parser;
 mod common;
 
 fn main() {
-    util::DayInput::find::<24>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
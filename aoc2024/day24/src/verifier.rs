@@ -0,0 +1,574 @@
+//! Structural verification of a device against the circuit it is supposed to be.
+//!
+//! Instead of searching through swaps, every bit is checked against the gates expected for it,
+//! starting from the `xNN`/`yNN` inputs, which cannot be swapped.
+//! The first gate that does not fit the pattern tells which two outputs need to be swapped,
+//! so repairing the device bit by bit also produces the swaps.
+//!
+//! A ripple-carry adder is built from one full adder per bit (a half adder for bit 0):
+//! ```text
+//! xNN XOR yNN -> p        (partial sum)
+//! xNN AND yNN -> g        (generated carry)
+//! p XOR carry -> zNN      (sum)
+//! p AND carry -> t        (propagated carry)
+//! g OR t -> carry         (carry into the next bit, or the last z-wire)
+//! ```
+use crate::circuit::*;
+use crate::common::*;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use util::random::XorShift;
+
+/// What the device is supposed to compute from `x` and `y`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Device {
+    /// `xNN AND yNN -> zNN`, like the example.
+    BitwiseAnd,
+    RippleCarryAdder,
+}
+impl Device {
+    /// The example only consists of `AND` gates, every actual input is an adder.
+    pub fn of(connections: &Connections) -> Self {
+        if connections.iter().all(|c| c.gate == Gate::And) {
+            Device::BitwiseAnd
+        } else {
+            Device::RippleCarryAdder
+        }
+    }
+
+    #[allow(dead_code)] // only p2 confirms swaps by simulating
+    pub fn expected(&self, x: u64, y: u64) -> u64 {
+        match self {
+            Device::BitwiseAnd => x & y,
+            Device::RippleCarryAdder => x + y,
+        }
+    }
+}
+
+/// Two gates whose outputs had to be swapped to fit the pattern.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Swap<'s> {
+    pub bit: usize,
+    /// The gate that broke the pattern, before the swap.
+    pub broken: Connection<'s>,
+    /// The gate it had to swap outputs with, before the swap.
+    pub other: Connection<'s>,
+    pub reason: &'static str,
+}
+impl Display for Swap<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "bit {:02}: `{}` <-> `{}` ({})",
+            self.bit, self.broken, self.other, self.reason
+        )
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[allow(dead_code)] // the simulation errors are only raised when p2 confirms swaps
+pub enum VerificationError<'s> {
+    /// A gate that cannot be fixed by swapping outputs is missing.
+    MissingGate { bit: usize, expected: String },
+    /// Repairing a single bit did not converge.
+    Unrepairable { bit: usize },
+    /// The repaired device cannot be simulated.
+    Circuit(CircuitError<'s>),
+    /// More input bits than fit a `u64` next to the carry out of the highest bit.
+    TooWide { bits: usize },
+    /// The repaired device still computes something else.
+    WrongResult {
+        x: u64,
+        y: u64,
+        z: u64,
+        swaps: Vec<Swap<'s>>,
+    },
+}
+impl Display for VerificationError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerificationError::MissingGate { bit, expected } => {
+                write!(f, "bit {:02}: no gate `{}`", bit, expected)
+            }
+            VerificationError::Unrepairable { bit } => {
+                write!(f, "bit {:02}: swapping outputs does not fix it", bit)
+            }
+            VerificationError::Circuit(error) => write!(f, "repaired device is {}", error),
+            VerificationError::TooWide { bits } => write!(
+                f,
+                "{} input bits are too many to simulate, at most {} fit",
+                bits, MAX_INPUT_BITS
+            ),
+            VerificationError::WrongResult { x, y, z, swaps } => write!(
+                f,
+                "{} swap(s) found, but {} and {} still result in {}",
+                swaps.len(),
+                x,
+                y,
+                z
+            ),
+        }
+    }
+}
+
+/// The gates of a device, in a form where outputs can be swapped.
+#[derive(Clone)]
+struct Wiring<'s> {
+    gates: Vec<Connection<'s>>,
+    swaps: Vec<Swap<'s>>,
+}
+impl<'s> Wiring<'s> {
+    fn find(&self, gate: Gate, a: WireName<'s>, b: WireName<'s>) -> Option<&Connection<'s>> {
+        self.gates
+            .iter()
+            .find(|c| c.gate == gate && ((c.a == a && c.b == b) || (c.a == b && c.b == a)))
+    }
+
+    /// Other inputs of the gates of that type with `a` as one of their inputs.
+    fn others_with(
+        &self,
+        gate: Gate,
+        a: WireName<'s>,
+    ) -> impl Iterator<Item = WireName<'s>> + use<'_, 's> {
+        self.gates.iter().filter_map(move |c| match c {
+            c if c.gate == gate && c.a == a => Some(c.b),
+            c if c.gate == gate && c.b == a => Some(c.a),
+            _ => None,
+        })
+    }
+
+    fn find_with(&self, gate: Gate, a: WireName<'s>) -> Option<WireName<'s>> {
+        self.others_with(gate, a).next()
+    }
+
+    fn output(
+        &self,
+        gate: Gate,
+        a: WireName<'s>,
+        b: WireName<'s>,
+        bit: usize,
+    ) -> Result<WireName<'s>, VerificationError<'s>> {
+        self.find(gate, a, b)
+            .map(|c| c.dest)
+            .ok_or_else(|| VerificationError::MissingGate {
+                bit,
+                expected: format!("{} {} {}", a.0, gate, b.0),
+            })
+    }
+
+    /// Swap the outputs of the gates producing `broken` and `other`.
+    fn swap(
+        &mut self,
+        bit: usize,
+        broken: WireName<'s>,
+        other: WireName<'s>,
+        reason: &'static str,
+    ) {
+        let position = |wire| {
+            self.gates
+                .iter()
+                .position(|c| c.dest == wire)
+                .expect("every wire but x and y is a gate output")
+        };
+        let (broken_position, other_position) = (position(broken), position(other));
+        self.swaps.push(Swap {
+            bit,
+            broken: self.gates[broken_position].clone(),
+            other: self.gates[other_position].clone(),
+            reason,
+        });
+        self.gates[broken_position].dest = other;
+        self.gates[other_position].dest = broken;
+    }
+}
+
+/// Name of some wire, taken from the device so it lives as long as the input.
+fn wire<'s>(
+    states: &States<'s>,
+    connections: &Connections<'s>,
+    prefix: char,
+    bit: usize,
+) -> Option<WireName<'s>> {
+    let name = format!("{}{:02}", prefix, bit);
+    states
+        .keys()
+        .chain(connections.iter().map(|c| &c.dest))
+        .find(|w| w.0 == name)
+        .copied()
+}
+
+/// Amount of bits of `x` (and `y`).
+pub fn input_bits(states: &States) -> usize {
+    states.keys().filter(|w| w.0.starts_with('x')).count()
+}
+
+/// Repair the device bit by bit, returning the swaps needed, in order of the bits they were found in.
+pub fn find_swaps<'s>(
+    device: Device,
+    states: &States<'s>,
+    connections: &Connections<'s>,
+) -> Result<Vec<Swap<'s>>, VerificationError<'s>> {
+    let mut wiring = Wiring {
+        gates: connections.iter().cloned().collect(),
+        swaps: vec![],
+    };
+    let bits = input_bits(states);
+    let name = |prefix, bit| {
+        wire(states, connections, prefix, bit).ok_or(VerificationError::MissingGate {
+            bit,
+            expected: format!("-> {}{:02}", prefix, bit),
+        })
+    };
+    match device {
+        Device::BitwiseAnd => {
+            for bit in 0..bits {
+                let (x, y, z) = (name('x', bit)?, name('y', bit)?, name('z', bit)?);
+                let dest = wiring.output(Gate::And, x, y, bit)?;
+                if dest != z {
+                    wiring.swap(bit, dest, z, "must output the same bit");
+                }
+            }
+        }
+        Device::RippleCarryAdder => {
+            let (x, y, z) = (name('x', 0)?, name('y', 0)?, name('z', 0)?);
+            let sum = wiring.output(Gate::Xor, x, y, 0)?;
+            if sum != z {
+                wiring.swap(0, sum, z, "half adder sum must output z00");
+            }
+            let mut carry = wiring.output(Gate::And, x, y, 0)?;
+            let last = name('z', bits)?;
+            for bit in 1..bits {
+                let (x, y, z) = (name('x', bit)?, name('y', bit)?, name('z', bit)?);
+                let next = match bit + 1 < bits {
+                    true => Some((name('x', bit + 1)?, name('y', bit + 1)?)),
+                    false => None,
+                };
+                let carries_on = |wiring: &Wiring<'s>, carry| match next {
+                    Some((x, y)) => wiring
+                        .find(Gate::Xor, x, y)
+                        .and_then(|partial| wiring.find(Gate::Xor, partial.dest, carry))
+                        .is_some(),
+                    None => carry == last,
+                };
+                carry = repair_full_adder(&mut wiring, bit, (x, y, z), carry, &carries_on)?;
+            }
+            if carry != last {
+                wiring.swap(bits, carry, last, "last carry must output the last z-wire");
+            }
+        }
+    }
+    Ok(wiring.swaps)
+}
+
+/// Repair a single full adder, returning its carry output.
+/// `carries_on` tells whether a carry output goes into the next bit.
+fn repair_full_adder<'s>(
+    wiring: &mut Wiring<'s>,
+    bit: usize,
+    (x, y, z): (WireName<'s>, WireName<'s>, WireName<'s>),
+    mut carry_in: WireName<'s>,
+    carries_on: &dyn Fn(&Wiring<'s>, WireName<'s>) -> bool,
+) -> Result<WireName<'s>, VerificationError<'s>> {
+    // every swap fixes one more gate of the five, so more attempts means it cannot be fixed
+    for _ in 0..6 {
+        // inputs cannot be swapped, so these gates always exist
+        let partial = wiring.output(Gate::Xor, x, y, bit)?;
+        let generated = wiring.output(Gate::And, x, y, bit)?;
+
+        let Some(sum) = wiring.find(Gate::Xor, partial, carry_in).map(|c| c.dest) else {
+            if let Some(other) = wiring.find_with(Gate::Xor, carry_in) {
+                wiring.swap(bit, partial, other, "partial sum must go into the sum");
+            } else if let Some(other) = wiring.find_with(Gate::Xor, partial) {
+                wiring.swap(bit, carry_in, other, "carry must go into the sum");
+                carry_in = other;
+            } else {
+                return Err(VerificationError::MissingGate {
+                    bit,
+                    expected: format!("{} XOR {}", partial.0, carry_in.0),
+                });
+            }
+            continue;
+        };
+        if sum != z {
+            // the carry in is the only wire of this bit that is already fixed
+            wiring.swap(bit, sum, z, "sum must output its z-wire");
+            continue;
+        }
+        let propagated = wiring.output(Gate::And, partial, carry_in, bit)?;
+        let Some(carry_out) = wiring.find(Gate::Or, generated, propagated).map(|c| c.dest) else {
+            // either may also go into the carry of another bit,
+            // so prefer the swap after which the carry goes on into the next bit
+            let candidates =
+                wiring
+                    .others_with(Gate::Or, propagated)
+                    .map(|other| (generated, other, "generated carry must go into the carry"))
+                    .chain(wiring.others_with(Gate::Or, generated).map(|other| {
+                        (propagated, other, "propagated carry must go into the carry")
+                    }))
+                    .collect_vec();
+            let carry_after = |&(broken, other, _): &(WireName<'s>, WireName<'s>, &str)| {
+                let mut attempt = wiring.clone();
+                attempt.swap(bit, broken, other, "");
+                let (generated, propagated) = match broken == generated {
+                    true => (other, propagated),
+                    false => (generated, other),
+                };
+                attempt
+                    .find(Gate::Or, generated, propagated)
+                    .is_some_and(|c| carries_on(&attempt, c.dest))
+            };
+            let Some(&(broken, other, reason)) = candidates
+                .iter()
+                .find(|candidate| carry_after(candidate))
+                .or(candidates.first())
+            else {
+                return Err(VerificationError::MissingGate {
+                    bit,
+                    expected: format!("{} OR {}", generated.0, propagated.0),
+                });
+            };
+            wiring.swap(bit, broken, other, reason);
+            continue;
+        };
+        return Ok(carry_out);
+    }
+    Err(VerificationError::Unrepairable { bit })
+}
+
+/// Connections with the outputs of every swap exchanged.
+#[allow(dead_code)] // only p2 confirms swaps by simulating
+pub fn apply_swaps<'s>(connections: &Connections<'s>, swaps: &[Swap<'s>]) -> Connections<'s> {
+    let mut gates = connections.iter().cloned().collect_vec();
+    for swap in swaps {
+        for gate in gates.iter_mut() {
+            if gate.dest == swap.broken.dest {
+                gate.dest = swap.other.dest;
+            } else if gate.dest == swap.other.dest {
+                gate.dest = swap.broken.dest;
+            }
+        }
+    }
+    gates.into_iter().collect()
+}
+
+/// Inputs and outputs are simulated as `u64`, and the outputs are one bit wider.
+pub const MAX_INPUT_BITS: usize = 63;
+
+/// Output of the device for `x` and `y` through [`descend_graph_cached`],
+/// which only terminates for devices that [`Circuit::compile`] accepts.
+#[allow(dead_code)] // only p2 confirms swaps by simulating
+fn descended_output(states: &States, connections: &Connections, x: u64, y: u64) -> u64 {
+    let bit = |wire: &WireName| wire.0[1..].parse::<u32>().expect("wires end in their bit");
+    let mut known = states
+        .keys()
+        .map(|wire| {
+            let value = if wire.0.starts_with('x') { x } else { y };
+            (*wire, value >> bit(wire) & 1 == 1)
+        })
+        .collect::<States>();
+    connections
+        .iter()
+        .filter(|c| c.dest.0.starts_with('z'))
+        .fold(0, |z, c| {
+            z | (descend_graph_cached(&c.dest, connections, &mut known) as u64) << bit(&c.dest)
+        })
+}
+
+/// Confirm the swaps by simulating random inputs, plus the ones carrying through every bit.
+/// The compiled circuit simulates them all at once, and the carrying ones are checked again
+/// through [`descend_graph_cached`].
+#[allow(dead_code)] // only p2 confirms swaps by simulating
+pub fn confirm<'s>(
+    device: Device,
+    states: &States<'s>,
+    connections: &Connections<'s>,
+    swaps: Vec<Swap<'s>>,
+    trials: usize,
+) -> Result<Vec<Swap<'s>>, VerificationError<'s>> {
+    let bits = input_bits(states);
+    if bits > MAX_INPUT_BITS {
+        return Err(VerificationError::TooWide { bits });
+    }
+    let connections = apply_swaps(connections, &swaps);
+    let repaired = Circuit::compile(states, &connections).map_err(VerificationError::Circuit)?;
+    let mask = (1u64 << repaired.input_bits()) - 1;
+    let carrying = [(0, 0), (mask, mask), (mask, 1), (1, mask)];
+    let mut rng = XorShift::new(0x2024_0024_5eed_0001);
+    let (xs, ys): (Vec<_>, Vec<_>) = carrying
+        .into_iter()
+        .chain((0..trials).map(|_| (rng.next_u64() & mask, rng.next_u64() & mask)))
+        .unzip();
+    let simulated = xs
+        .iter()
+        .zip(&ys)
+        .zip(repaired.outputs(&xs, &ys))
+        .map(|((&x, &y), z)| (x, y, z));
+    let descended = carrying
+        .into_iter()
+        .map(|(x, y)| (x, y, descended_output(states, &connections, x, y)));
+    match simulated
+        .chain(descended)
+        .find(|&(x, y, z)| z != device.expected(x, y))
+    {
+        Some((x, y, z)) => Err(VerificationError::WrongResult { x, y, z, swaps }),
+        None => Ok(swaps),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winnow::Parser;
+
+    /// A correct ripple-carry adder of `bits` bits, with the outputs of `swaps` exchanged.
+    fn adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let rename = |wire: String| {
+            swaps
+                .iter()
+                .find_map(|&(a, b)| match wire.as_str() {
+                    w if w == a => Some(b.to_string()),
+                    w if w == b => Some(a.to_string()),
+                    _ => None,
+                })
+                .unwrap_or(wire)
+        };
+        let inputs = (0..bits)
+            .map(|bit| format!("x{:02}: 1", bit))
+            .chain((0..bits).map(|bit| format!("y{:02}: 0", bit)))
+            .join("\n");
+        let mut gates = vec![
+            format!("x00 XOR y00 -> {}", rename("z00".into())),
+            format!("x00 AND y00 -> {}", rename("c00".into())),
+        ];
+        for bit in 1..bits {
+            let carry_in = format!("c{:02}", bit - 1);
+            let carry_out = if bit == bits - 1 {
+                format!("z{:02}", bits)
+            } else {
+                format!("c{:02}", bit)
+            };
+            gates.extend([
+                format!(
+                    "x{0:02} XOR y{0:02} -> {1}",
+                    bit,
+                    rename(format!("p{:02}", bit))
+                ),
+                format!(
+                    "y{0:02} AND x{0:02} -> {1}",
+                    bit,
+                    rename(format!("g{:02}", bit))
+                ),
+                format!(
+                    "{1} XOR p{0:02} -> {2}",
+                    bit,
+                    carry_in,
+                    rename(format!("z{:02}", bit))
+                ),
+                format!(
+                    "p{0:02} AND {1} -> {2}",
+                    bit,
+                    carry_in,
+                    rename(format!("t{:02}", bit))
+                ),
+                format!("g{0:02} OR t{0:02} -> {1}", bit, rename(carry_out)),
+            ]);
+        }
+        format!("{}\n\n{}", inputs, gates.join("\n"))
+    }
+
+    fn swapped_wires(input: &str) -> Result<String, String> {
+        let (states, connections) = parse_device.parse_next(&mut &*input).expect("parsable");
+        let device = Device::of(&connections);
+        find_swaps(device, &states, &connections)
+            .and_then(|swaps| confirm(device, &states, &connections, swaps, 100))
+            .map(|swaps| {
+                swaps
+                    .iter()
+                    .flat_map(|swap| [swap.broken.dest.0, swap.other.dest.0])
+                    .sorted()
+                    .join(",")
+            })
+            .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn correct_adder_needs_no_swaps() {
+        assert_eq!(swapped_wires(&adder(45, &[])), Ok("".to_string()));
+    }
+
+    #[test]
+    fn widest_adder_confirmed() {
+        assert_eq!(swapped_wires(&adder(63, &[])), Ok("".to_string()));
+        assert_eq!(
+            swapped_wires(&adder(64, &[])),
+            Err("64 input bits are too many to simulate, at most 63 fit".to_string())
+        );
+    }
+
+    #[test]
+    fn simulated_adder_adds() {
+        let input = adder(8, &[]);
+        let (states, connections) = parse_device.parse_next(&mut &*input).expect("parsable");
+        let circuit = Circuit::compile(&states, &connections).expect("acyclic");
+        for (x, y) in [(0, 0), (255, 1), (200, 100), (17, 4)] {
+            assert_eq!(circuit.output(x, y), x + y);
+        }
+    }
+
+    #[test]
+    fn swapped_adder_repairable() {
+        let input = adder(
+            45,
+            &[
+                ("z05", "t05"),
+                ("p10", "g10"),
+                ("z15", "c15"),
+                ("z20", "g20"),
+            ],
+        );
+        assert_eq!(
+            swapped_wires(&input),
+            Ok("c15,g10,g20,p10,t05,z05,z15,z20".to_string())
+        );
+    }
+
+    #[test]
+    fn swapped_carries_repairable() {
+        // carries swapped with the gates reading them, feeding them, or the last z-wire
+        // (swapping both inputs of the same gate would not change anything, like `p04` and `c03`)
+        let input = adder(
+            12,
+            &[
+                ("c03", "g04"),
+                ("t08", "g09"),
+                ("z00", "c00"),
+                ("c10", "z12"),
+            ],
+        );
+        assert_eq!(
+            swapped_wires(&input),
+            Ok("c00,c03,c10,g04,g09,t08,z00,z12".to_string())
+        );
+    }
+
+    #[test]
+    fn swaps_report_broken_gates() {
+        let input = adder(4, &[("z02", "t02")]);
+        let (states, connections) = parse_device.parse_next(&mut &*input).expect("parsable");
+        let swaps =
+            find_swaps(Device::RippleCarryAdder, &states, &connections).expect("repairable");
+        assert_eq!(
+            swaps.iter().map(ToString::to_string).collect_vec(),
+            ["bit 02: `c01 XOR p02 -> t02` <-> `p02 AND c01 -> z02` (sum must output its z-wire)"]
+        );
+    }
+
+    #[test]
+    fn missing_gates_reported() {
+        let input = adder(4, &[]).replace("g02 OR t02", "g02 AND t02");
+        assert_eq!(
+            swapped_wires(&input),
+            Err("bit 02: no gate `g02 OR t02`".to_string())
+        );
+    }
+}
@@ -12,7 +12,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<25>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 fn solve(input: impl AsRef<str>) -> u64 {
@@ -10,7 +10,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<3>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 #[derive(Debug, Clone)]
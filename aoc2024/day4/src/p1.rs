@@ -14,7 +14,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<4>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Constraints
@@ -12,7 +12,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<4>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// Count amount of X's (diagonal lines; cardinal can be ignored) made of `MAS`,
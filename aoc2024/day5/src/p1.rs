@@ -10,7 +10,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<5>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Specification
@@ -15,7 +15,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<5>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// Use previous knowledge to create some sufficient sorted set, then apply to
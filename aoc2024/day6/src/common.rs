@@ -0,0 +1,361 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[allow(unused_imports)]
+use winnow::{
+    ascii::*,
+    combinator::*,
+    error::*,
+    stream::AsChar,
+    token::*,
+    {PResult, Parser},
+};
+
+pub struct SimulationState {
+    pub guard: Guard,
+    pub obstacles: HashSet<Position>,
+    pub bounds: MapBounds,
+}
+impl Display for SimulationState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..(self.bounds.height as i64) {
+            for x in 0..(self.bounds.width as i64) {
+                let pos = Position { x, y };
+                write!(
+                    f,
+                    "{}",
+                    if self.guard.pos == pos {
+                        (&self.guard.dir).into()
+                    } else if self.obstacles.contains(&pos) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+impl SimulationState {
+    pub fn finish(mut self, mut visited: HashSet<Position>) -> CompletedSimulation {
+        self.step_till_completed(&mut visited);
+        CompletedSimulation { sim: self, visited }
+    }
+
+    pub fn step_till_completed(&mut self, visited: &mut HashSet<Position>) {
+        while self.guard.pos.is_inside(&self.bounds) {
+            self.step_and_or_turn(visited);
+        }
+    }
+
+    #[allow(unused)]
+    #[cfg(test)]
+    pub fn walk_to_next_collision(&mut self, visited: &mut HashSet<Position>) {
+        let _ = self.turn_till_free();
+        while self.free_step(visited) {}
+    }
+
+    #[allow(unused)]
+    #[cfg(test)]
+    pub fn free_step(&mut self, visited: &mut HashSet<Position>) -> bool {
+        let possible_new_pos = self.guard.pos.move_in(&self.guard.dir);
+        if self.obstacles.contains(&possible_new_pos) || !possible_new_pos.is_inside(&self.bounds) {
+            false
+        } else {
+            self.guard.pos = possible_new_pos;
+            visited.insert(self.guard.pos);
+            true
+        }
+    }
+
+    pub fn step_and_or_turn(&mut self, visited: &mut HashSet<Position>) {
+        let possible_new_pos = self.turn_till_free();
+        self.guard.pos = possible_new_pos;
+        if self.guard.pos.is_inside(&self.bounds) {
+            visited.insert(self.guard.pos);
+        }
+    }
+
+    pub fn turn_till_free(&mut self) -> Position {
+        let mut possible_new_pos;
+        while {
+            // try facing direction
+            possible_new_pos = self.guard.pos.move_in(&self.guard.dir);
+            self.obstacles.contains(&possible_new_pos)
+        } {
+            // face new direction
+            self.guard.dir = self.guard.dir.next();
+        }
+        possible_new_pos
+    }
+}
+pub struct Guard {
+    pub pos: Position,
+    pub dir: Direction,
+}
+#[derive(Debug, Clone)]
+pub struct MapBounds {
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Clone)]
+pub enum MapItem {
+    Empty,
+    Guard(Direction),
+    Obstacle,
+}
+impl MapItem {
+    pub fn parser(s: &mut &str) -> PResult<Self> {
+        alt((
+            '.'.value(Self::Empty),
+            '#'.value(Self::Obstacle),
+            Direction::parser.map(Self::Guard),
+        ))
+        .parse_next(s)
+    }
+}
+impl FromStr for SimulationState {
+    type Err = ErrMode<ContextError>;
+
+    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
+        let bounds = MapBounds {
+            width: s.lines().next().expect("first line").chars().count(),
+            height: s.lines().count(),
+        };
+        parse_content
+            .parse_next(&mut s)
+            .map(|(guard, obstacles)| SimulationState {
+                guard,
+                obstacles,
+                bounds,
+            })
+    }
+}
+fn parse_content(input: &mut &str) -> PResult<(Guard, HashSet<Position>)> {
+    let all_lines: Vec<_> = separated(1.., parse_line, line_ending).parse_next(input)?;
+    let (guard, obstacles) = all_lines
+        .into_iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.into_iter()
+                .enumerate()
+                .map(move |(x, item)| ((x, y), item))
+        })
+        .fold(
+            (None, HashSet::new()),
+            |(mut guard, mut obstacles), ((x, y), item)| {
+                match item {
+                    MapItem::Empty => {}
+                    MapItem::Guard(dir) => {
+                        guard = Some(Guard {
+                            pos: Position {
+                                x: x as i64,
+                                y: y as i64,
+                            },
+                            dir,
+                        })
+                    }
+                    MapItem::Obstacle => {
+                        if !obstacles.insert(Position {
+                            x: x as i64,
+                            y: y as i64,
+                        }) {
+                            panic!("it should be impossible for this to exist")
+                        }
+                    }
+                };
+                (guard, obstacles)
+            },
+        );
+    Ok((guard.expect("there should be a guard somewhere"), obstacles))
+}
+fn parse_line(input: &mut &str) -> PResult<Vec<MapItem>> {
+    repeat(1.., MapItem::parser).parse_next(input)
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub struct Position {
+    pub x: i64,
+    pub y: i64,
+}
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+impl Position {
+    pub fn is_inside(&self, bounds: &MapBounds) -> bool {
+        (0..bounds.width as i64).contains(&self.x) && (0..bounds.height as i64).contains(&self.y)
+    }
+
+    pub fn move_in(&self, dir: &Direction) -> Self {
+        match dir {
+            // notice that positions are upside down for line counting reasons
+            Direction::Up => Self {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::Down => Self {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::Left => Self {
+                x: self.x - 1,
+                y: self.y,
+            },
+            Direction::Right => Self {
+                x: self.x + 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", char::from(self))
+    }
+}
+impl From<&Direction> for char {
+    fn from(dir: &Direction) -> Self {
+        match dir {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+}
+impl Direction {
+    pub fn parser(s: &mut &str) -> PResult<Direction> {
+        alt((
+            '^'.value(Direction::Up),
+            '>'.value(Direction::Right),
+            'v'.value(Direction::Down),
+            '<'.value(Direction::Left),
+        ))
+        .parse_next(s)
+    }
+
+    pub fn next(&self) -> Direction {
+        match self {
+            // always turn clockwise
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+}
+
+pub struct CompletedSimulation {
+    pub sim: SimulationState,
+    pub visited: HashSet<Position>,
+}
+impl Display for CompletedSimulation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..(self.sim.bounds.height as i64) {
+            for x in 0..(self.sim.bounds.width as i64) {
+                let pos = Position { x, y };
+                write!(
+                    f,
+                    "{}",
+                    if self.sim.guard.pos == pos {
+                        (&self.sim.guard.dir).into()
+                    } else if self.sim.obstacles.contains(&pos) {
+                        '#'
+                    } else if self.visited.contains(&pos) {
+                        'X'
+                    } else {
+                        '.'
+                    }
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Obstacles of every row and column, sorted, to find the next obstacle in some direction
+/// with a binary search instead of walking cell by cell.
+#[allow(dead_code)] // p1 walks the single patrol cell by cell
+pub struct JumpTable {
+    /// `x` of the obstacles in every row.
+    rows: Vec<Vec<i64>>,
+    /// `y` of the obstacles in every column.
+    columns: Vec<Vec<i64>>,
+}
+#[allow(dead_code)] // p1 walks the single patrol cell by cell
+impl JumpTable {
+    pub fn new(sim: &SimulationState) -> Self {
+        let mut rows = vec![vec![]; sim.bounds.height];
+        let mut columns = vec![vec![]; sim.bounds.width];
+        for obstacle in &sim.obstacles {
+            rows[obstacle.y as usize].push(obstacle.x);
+            columns[obstacle.x as usize].push(obstacle.y);
+        }
+        rows.iter_mut().for_each(|row| row.sort_unstable());
+        columns.iter_mut().for_each(|column| column.sort_unstable());
+        JumpTable { rows, columns }
+    }
+
+    /// Where the guard stops walking in some direction, right before an obstacle
+    /// (possibly `extra`, an obstacle not in the table), or `None` if they leave the map.
+    pub fn next_collision(
+        &self,
+        from: Position,
+        dir: Direction,
+        extra: Option<Position>,
+    ) -> Option<Position> {
+        // coordinate along the line of movement, of the line, and whether it increases
+        let (along, line, lines, forwards) = match dir {
+            Direction::Up => (from.y, from.x, &self.columns, false),
+            Direction::Down => (from.y, from.x, &self.columns, true),
+            Direction::Left => (from.x, from.y, &self.rows, false),
+            Direction::Right => (from.x, from.y, &self.rows, true),
+        };
+        let obstacles = &lines[line as usize];
+        let ahead = if forwards {
+            obstacles
+                .get(obstacles.partition_point(|&o| o <= along))
+                .copied()
+        } else {
+            obstacles[..obstacles.partition_point(|&o| o < along)]
+                .last()
+                .copied()
+        };
+        let extra_ahead = extra
+            .map(|extra| match dir {
+                Direction::Up | Direction::Down => (extra.y, extra.x),
+                Direction::Left | Direction::Right => (extra.x, extra.y),
+            })
+            .filter(|&(extra_along, extra_line)| {
+                extra_line == line && (extra_along > along) == forwards && extra_along != along
+            })
+            .map(|(extra_along, _)| extra_along);
+        let obstacle = match (ahead, extra_ahead) {
+            (Some(a), Some(b)) if forwards => a.min(b),
+            (Some(a), Some(b)) => a.max(b),
+            (a, b) => a.or(b)?,
+        };
+        let stop = if forwards { obstacle - 1 } else { obstacle + 1 };
+        Some(match dir {
+            Direction::Up | Direction::Down => Position { x: line, y: stop },
+            Direction::Left | Direction::Right => Position { x: stop, y: line },
+        })
+    }
+}
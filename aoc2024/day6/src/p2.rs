@@ -1,5 +1,11 @@
 #![doc = include_str!("../p2.md")]
 
+mod common;
+
+use common::*;
+use std::collections::HashSet;
+use std::thread;
+
 #[allow(unused_imports)]
 use winnow::{
     ascii::*,
@@ -10,7 +16,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<6>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
@@ -28,11 +34,137 @@ fn main() {
 /// This can be done with BFS. Go along the standard guard path, and for every
 /// step check if the different path created by an obstacle creates an infinite
 /// loop or not, using the above method.
+/// ## Jumping
+/// Only the spots where the guard turns matter for finding repeats, so instead of walking,
+/// the guard jumps straight to the next obstacle using a [`JumpTable`].
+/// Every candidate obstacle is independent of the others, so they are checked in parallel.
 fn solve(input: impl AsRef<str>) -> u64 {
-    todo!()
+    let sim = input.as_ref().parse::<SimulationState>().expect("parsable");
+    let start = Guard { ..sim.guard };
+    let table = JumpTable::new(&sim);
+    // the guard would notice an obstacle placed where they stand
+    let candidates = sim
+        .finish(HashSet::new())
+        .visited
+        .into_iter()
+        .filter(|&pos| pos != start.pos)
+        .collect::<Vec<_>>();
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let (table, start) = (&table, &start);
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter(|&&obstacle| loops(table, start, obstacle))
+                        .count() as u64
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().expect("no panics"))
+            .sum()
+    })
 }
+
+/// Whether the guard walks in a loop with an added obstacle,
+/// meaning they stop before an obstacle in the same spot facing the same direction twice.
+fn loops(table: &JumpTable, start: &Guard, obstacle: Position) -> bool {
+    let (mut pos, mut dir) = (start.pos, start.dir);
+    let mut stops = HashSet::new();
+    while let Some(stop) = table.next_collision(pos, dir, Some(obstacle)) {
+        if !stops.insert((stop, dir)) {
+            return true;
+        }
+        (pos, dir) = (stop, dir.next());
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    /// Walking cell by cell, like part one.
+    fn loops_walking(sim: &SimulationState, obstacle: Position) -> bool {
+        let mut obstacles = sim.obstacles.clone();
+        obstacles.insert(obstacle);
+        let mut sim = SimulationState {
+            guard: Guard { ..sim.guard },
+            obstacles,
+            bounds: sim.bounds.clone(),
+        };
+        let mut seen = HashSet::new();
+        while sim.guard.pos.is_inside(&sim.bounds) {
+            if !seen.insert((sim.guard.pos, sim.guard.dir)) {
+                return true;
+            }
+            sim.step_and_or_turn(&mut HashSet::new());
+        }
+        false
+    }
+
+    #[test]
+    fn jumps_match_walking() {
+        let sim = include_str!("EXAMPLE_0")
+            .parse::<SimulationState>()
+            .expect("parsable");
+        let table = JumpTable::new(&sim);
+        for x in 0..sim.bounds.width as i64 {
+            for y in 0..sim.bounds.height as i64 {
+                let obstacle = Position { x, y };
+                if sim.obstacles.contains(&obstacle) || obstacle == sim.guard.pos {
+                    continue;
+                }
+                assert_eq!(
+                    loops(&table, &sim.guard, obstacle),
+                    loops_walking(&sim, obstacle),
+                    "obstacle at {}",
+                    obstacle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn collisions_found() {
+        let sim = include_str!("EXAMPLE_0")
+            .parse::<SimulationState>()
+            .expect("parsable");
+        let table = JumpTable::new(&sim);
+        let at = |x, y| Position { x, y };
+        assert_eq!(
+            table.next_collision(at(4, 6), Direction::Up, None),
+            Some(at(4, 1))
+        );
+        assert_eq!(
+            table.next_collision(at(4, 6), Direction::Up, Some(at(4, 3))),
+            Some(at(4, 4))
+        );
+        // behind the guard does not count
+        assert_eq!(
+            table.next_collision(at(4, 6), Direction::Up, Some(at(4, 7))),
+            Some(at(4, 1))
+        );
+        assert_eq!(
+            table.next_collision(at(4, 1), Direction::Right, None),
+            Some(at(8, 1))
+        );
+        assert_eq!(table.next_collision(at(4, 6), Direction::Down, None), None);
+        assert_eq!(
+            table.next_collision(at(2, 6), Direction::Left, None),
+            Some(at(2, 6))
+        );
+    }
+
+    #[test]
+    fn example_solvable() {
+        assert_eq!(super::solve(include_str!("EXAMPLE_0")), 6);
+    }
 
     #[test]
     fn input_solvable() {
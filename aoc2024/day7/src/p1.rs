@@ -13,7 +13,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<7>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 #[derive(Debug)]
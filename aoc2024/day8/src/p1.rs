@@ -13,7 +13,7 @@ use itertools::Itertools;
 use winnow::error::{ContextError, ErrMode};
 
 fn main() {
-    util::DayInput::find::<8>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
@@ -20,7 +20,7 @@ use winnow::{
 };
 
 fn main() {
-    util::DayInput::find::<8>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Problem
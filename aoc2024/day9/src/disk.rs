@@ -0,0 +1,398 @@
+//! The disk described by a [`DiskMap`], and the ways of compacting it.
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt::{Display, Formatter},
+};
+
+use itertools::Itertools;
+
+use crate::common::*;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Block<I> {
+    pub index: I,
+    pub len: BlockLen,
+}
+pub type File = Block<FileIndex>;
+pub type FreeSpace = Block<FreeSpaceIndex>;
+impl Display for File {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for _ in 0..self.len.0 {
+            write!(f, "{}", self.index.id.0)?;
+        }
+        Ok(())
+    }
+}
+impl Display for FreeSpace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for _ in 0..self.len.0 {
+            write!(f, ".")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every file (or piece of one) and free space, both sorted by position.
+#[derive(Debug, Clone)]
+pub struct Disk {
+    pub files: Vec<File>,
+    pub free_spaces: Vec<FreeSpace>,
+    /// Amount of blocks, free or not.
+    pub len: usize,
+}
+
+impl From<&DiskMap> for Disk {
+    fn from(disk_map: &DiskMap) -> Self {
+        let mut disk = Disk {
+            files: Vec::new(),
+            free_spaces: Vec::new(),
+            len: 0,
+        };
+        for (id, &(file_len, free_space_len)) in disk_map.0.iter().enumerate() {
+            disk.files.push(File {
+                index: FileIndex {
+                    pos: Position(disk.len),
+                    id: ID(id),
+                },
+                len: file_len,
+            });
+            disk.len += file_len.0 as usize;
+            // if the total block count is odd,
+            // there is no free space block to go along with the last file block.
+            if let Some(free_space_len) = free_space_len {
+                disk.free_spaces.push(FreeSpace {
+                    index: FreeSpaceIndex {
+                        pos: Position(disk.len),
+                    },
+                    len: free_space_len,
+                });
+                disk.len += free_space_len.0 as usize;
+            }
+        }
+        disk.settle();
+        disk
+    }
+}
+
+impl Disk {
+    pub fn compacted(mut self, compaction: &impl Compaction) -> Self {
+        compaction.compact(&mut self);
+        self.settle();
+        self
+    }
+
+    /// The sum of each file block ID times its position.
+    pub fn checksum(&self) -> u64 {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                let start = file.index.pos.0;
+                (start..start + file.len.0 as usize).map(|pos| (pos * file.index.id.0) as u64)
+            })
+            .sum()
+    }
+
+    /// Which file every block belongs to, if any.
+    #[allow(dead_code)] // only p1 moves single blocks
+    fn blocks(&self) -> Vec<Option<ID>> {
+        let mut blocks = vec![None; self.len];
+        for file in &self.files {
+            let start = file.index.pos.0;
+            blocks[start..start + file.len.0 as usize].fill(Some(file.index.id));
+        }
+        blocks
+    }
+
+    /// Sorts the files after they were moved, and finds the free spaces between them.
+    /// Free spaces left next to each other are merged, as long as that fits a [`BlockLen`].
+    fn settle(&mut self) {
+        self.files.retain(|file| file.len.0 != 0);
+        self.files.sort_by_key(|file| file.index.pos.0);
+        let ends = self
+            .files
+            .iter()
+            .map(|file| (file.index.pos.0, file.index.pos.0 + file.len.0 as usize));
+        let mut free_spaces = Vec::new();
+        let mut pos = 0;
+        for (start, end) in ends.chain([(self.len, self.len)]) {
+            debug_assert!(pos <= start, "files should not overlap");
+            while pos < start {
+                let len = (start - pos).min(u8::MAX as usize);
+                free_spaces.push(FreeSpace {
+                    index: FreeSpaceIndex { pos: Position(pos) },
+                    len: BlockLen(len as u8),
+                });
+                pos += len;
+            }
+            pos = end;
+        }
+        self.free_spaces = free_spaces;
+    }
+}
+
+/// Blocks in order, a file ID for every file block and `.` for every free one.
+impl Display for Disk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for block in self
+            .files
+            .iter()
+            .map(|file| (file.index.pos.0, Ok(file)))
+            .merge_by(
+                self.free_spaces
+                    .iter()
+                    .map(|space| (space.index.pos.0, Err(space))),
+                |(a, _), (b, _)| a <= b,
+            )
+            .map(|(_, block)| block)
+        {
+            match block {
+                Ok(file) => Display::fmt(file, f)?,
+                Err(space) => Display::fmt(space, f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A way of moving files into free space further left.
+pub trait Compaction {
+    /// Moves files around, without having to keep [`Disk::free_spaces`] up to date.
+    fn compact(&self, disk: &mut Disk);
+}
+
+/// Moves single blocks from the right into the leftmost free block,
+/// until there's no free block left between two file blocks.
+/// Files get split up into pieces along the way.
+#[derive(Debug, Copy, Clone)]
+#[allow(dead_code)] // only p1 moves single blocks
+pub struct BlockLevel;
+impl Compaction for BlockLevel {
+    fn compact(&self, disk: &mut Disk) {
+        let mut blocks = disk.blocks();
+        let (mut left, mut right) = (0, blocks.len());
+        loop {
+            while left < right && blocks[left].is_some() {
+                left += 1;
+            }
+            while left < right && blocks[right - 1].is_none() {
+                right -= 1;
+            }
+            if left >= right {
+                break;
+            }
+            blocks.swap(left, right - 1);
+        }
+        let mut pos = 0;
+        disk.files.clear();
+        for (id, run) in &blocks.iter().chunk_by(|block| block.map(|id| id.0)) {
+            let len = run.count();
+            if let Some(id) = id {
+                // a run longer than a block length gets split in two
+                for (start, len) in (pos..pos + len)
+                    .step_by(u8::MAX as usize)
+                    .map(|start| (start, (pos + len - start).min(u8::MAX as usize)))
+                {
+                    disk.files.push(File {
+                        index: FileIndex {
+                            pos: Position(start),
+                            id: ID(id),
+                        },
+                        len: BlockLen(len as u8),
+                    });
+                }
+            }
+            pos += len;
+        }
+    }
+}
+
+/// Moves every whole file once, starting with the highest ID, into the leftmost free space
+/// it fits in. Files that don't fit anywhere to their left stay where they are.
+#[derive(Debug, Copy, Clone)]
+#[allow(dead_code)] // only p2 moves whole files
+pub struct FirstFit;
+impl Compaction for FirstFit {
+    fn compact(&self, disk: &mut Disk) {
+        move_whole_files(disk, |heaps, len, before| {
+            heaps
+                .fitting(len, before)
+                .min_by_key(|&(_, pos)| pos)
+                .map(|(space_len, _)| space_len)
+        });
+    }
+}
+
+/// Moves every whole file once, starting with the highest ID, into the smallest free space
+/// it fits in, preferring the leftmost one of that size.
+/// Exact fits leave no slivers of free space behind, so bigger free spaces stay around for
+/// bigger files, at the cost of files ending up less far left.
+#[derive(Debug, Copy, Clone)]
+#[allow(dead_code)] // only p2 moves whole files
+pub struct BestFit;
+impl Compaction for BestFit {
+    fn compact(&self, disk: &mut Disk) {
+        move_whole_files(disk, |heaps, len, before| {
+            heaps
+                .fitting(len, before)
+                .next()
+                .map(|(space_len, _)| space_len)
+        });
+    }
+}
+
+/// Free space positions, one min-heap per length, so the leftmost free space of a length is
+/// always on top.
+#[allow(dead_code)] // only p2 moves whole files
+struct FreeSpaceHeaps(Vec<BinaryHeap<Reverse<usize>>>);
+
+#[allow(dead_code)] // only p2 moves whole files
+impl FreeSpaceHeaps {
+    fn new(free_spaces: &[FreeSpace]) -> Self {
+        let mut heaps = FreeSpaceHeaps(vec![BinaryHeap::new(); u8::MAX as usize + 1]);
+        for space in free_spaces {
+            heaps.push(space.len.0, space.index.pos.0);
+        }
+        heaps
+    }
+
+    fn push(&mut self, len: u8, pos: usize) {
+        if len != 0 {
+            self.0[len as usize].push(Reverse(pos));
+        }
+    }
+
+    /// Length and position of the leftmost free space of every length that fits `len` blocks
+    /// before position `before`, shortest length first.
+    fn fitting(&self, len: u8, before: usize) -> impl Iterator<Item = (u8, usize)> + '_ {
+        (len..=u8::MAX).filter_map(move |space_len| {
+            let Reverse(pos) = *self.0[space_len as usize].peek()?;
+            (pos < before).then_some((space_len, pos))
+        })
+    }
+}
+
+/// Moves files by descending ID into the free space length chosen by `pick`,
+/// which gets the heaps, the length of the file and its position.
+#[allow(dead_code)] // only p2 moves whole files
+fn move_whole_files(disk: &mut Disk, pick: impl Fn(&FreeSpaceHeaps, u8, usize) -> Option<u8>) {
+    let mut heaps = FreeSpaceHeaps::new(&disk.free_spaces);
+    disk.files.sort_by_key(|file| Reverse(file.index.id.0));
+    for file in &mut disk.files {
+        let Some(space_len) = pick(&heaps, file.len.0, file.index.pos.0) else {
+            continue;
+        };
+        let Reverse(pos) = heaps.0[space_len as usize]
+            .pop()
+            .expect("picked a free space");
+        file.index.pos = Position(pos);
+        // the space the file leaves behind is right of every file still to be moved,
+        // since IDs increase left to right, so nothing could ever move into it
+        heaps.push(space_len - file.len.0, pos + file.len.0 as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::random::XorShift;
+
+    fn compacted(input: &str, compaction: &impl Compaction) -> Disk {
+        let disk_map = input.trim().parse::<DiskMap>().expect("parsable");
+        Disk::from(&disk_map).compacted(compaction)
+    }
+
+    fn last_line(steps: &str) -> &str {
+        steps.lines().last().expect("non-empty")
+    }
+
+    #[test]
+    fn layouts_displayed() {
+        let disk_map = include_str!("EXAMPLE").parse::<DiskMap>().unwrap();
+        assert_eq!(
+            Disk::from(&disk_map).to_string(),
+            include_str!("EXAMPLE_BLOCKS").trim()
+        );
+        let disk_map = include_str!("BASIC").parse::<DiskMap>().unwrap();
+        assert_eq!(
+            Disk::from(&disk_map).to_string(),
+            include_str!("BASIC_BLOCKS").trim()
+        );
+    }
+
+    #[test]
+    fn block_level_compacted() {
+        let disk = compacted(include_str!("EXAMPLE"), &BlockLevel);
+        assert_eq!(
+            disk.to_string(),
+            last_line(include_str!("EXAMPLE_COMPACTING"))
+        );
+        assert_eq!(disk.checksum(), 1928);
+        let disk = compacted(include_str!("BASIC"), &BlockLevel);
+        assert_eq!(
+            disk.to_string(),
+            last_line(include_str!("BASIC_COMPACTING"))
+        );
+    }
+
+    #[test]
+    fn first_fit_compacted() {
+        let disk = compacted(include_str!("EXAMPLE"), &FirstFit);
+        assert_eq!(
+            disk.to_string(),
+            last_line(include_str!("EXAMPLE_COMPACTING_P2"))
+        );
+        assert_eq!(disk.checksum(), 2858);
+    }
+
+    #[test]
+    fn best_fit_compacted() {
+        // 3 takes the single free block after 1 instead of the first of three free blocks,
+        // which leaves room for 2 further left, and then for 1
+        assert_eq!(compacted("1311201", &BestFit).to_string(), "0221.3...");
+        assert_eq!(compacted("1311201", &FirstFit).to_string(), "03221....");
+        // every file in the example fits either the smallest or the leftmost free space
+        let disk = compacted(include_str!("EXAMPLE"), &BestFit);
+        assert_eq!(
+            disk.to_string(),
+            last_line(include_str!("EXAMPLE_COMPACTING_P2"))
+        );
+    }
+
+    /// First fit the slow way, scanning every free space for every file.
+    fn first_fit_scanned(disk: &mut Disk) {
+        for file in disk.files.iter_mut().rev() {
+            if let Some(space) = disk
+                .free_spaces
+                .iter_mut()
+                .find(|space| space.index.pos.0 < file.index.pos.0 && space.len.0 >= file.len.0)
+            {
+                file.index.pos = space.index.pos;
+                space.index.pos.0 += file.len.0 as usize;
+                space.len.0 -= file.len.0;
+            }
+        }
+    }
+
+    #[test]
+    fn heaps_match_scanning() {
+        let mut random = XorShift::default();
+        for _ in 0..50 {
+            let digits = (0..41)
+                .map(|n| match random.below(10) as u8 {
+                    // files are never empty
+                    0 if n % 2 == 0 => '1',
+                    digit => char::from(b'0' + digit),
+                })
+                .collect::<String>();
+            let disk = Disk::from(&digits.parse::<DiskMap>().unwrap());
+            let mut scanned = disk.clone();
+            first_fit_scanned(&mut scanned);
+            scanned.settle();
+            let disk = disk.compacted(&FirstFit);
+            assert_eq!(disk.to_string(), scanned.to_string(), "{}", digits);
+            assert_eq!(disk.checksum(), scanned.checksum());
+        }
+    }
+}
@@ -1,12 +1,13 @@
 #![doc = include_str!("../p1.md")]
 
 mod common;
+mod disk;
+
 use common::*;
-use itertools::{EitherOrBoth, Itertools};
-use winnow::Parser;
+use disk::*;
 
 fn main() {
-    util::DayInput::find::<9>().solve_with(solve);
+    util::day_input!().solve_with(solve);
 }
 
 /// # Input
@@ -49,62 +50,11 @@ fn main() {
 /// trivial `enumerate`-operation. We are allowed to separate the files from the
 /// free space as long as both know their original position, so that "swapping"
 /// (or calculating as-if) has the right effect in the result.
+/// ## Compaction
+/// All of that is [`BlockLevel`] compaction on the shared [`Disk`].
 fn solve(input: impl AsRef<str>) -> u64 {
-    let disk_map = input.as_ref().parse::<DiskMap>().expect("parsable");
-    let (files, file_spaces, _) = disk_map
-		.0
-		.into_iter()
-		// enumerating here gives us file (block) IDs
-		.enumerate()
-		.map(|(id_n, rest)| (ID(id_n), rest))
-		.fold(
-			(Vec::new(), Vec::new(), Position(0)),
-			|(mut files, mut free_space, mut shared_pos): (
-				Vec<FileIndex>,
-				Vec<FreeSpaceIndex>,
-				Position
-			),
-			 (id, (file_block_len, free_space_len)): (ID, (BlockLen, Option<BlockLen>))| {
-				for _ in 0..file_block_len.0 {
-					files.push(FileIndex {
-						pos: shared_pos,
-						id
-					});
-					shared_pos.0 += 1;
-				}
-				// if the total block count is odd,
-				// there is no free space block to go along with the last file block.
-				if let Some(free_space_len) = free_space_len {
-					for _ in 0..free_space_len.0 {
-						free_space.push(FreeSpaceIndex { pos: shared_pos });
-						shared_pos.0 += 1;
-					}
-				}
-
-				(files, free_space, shared_pos)
-			}
-		);
-
-    // both vectors semantically go from left to right until file iterator is
-    // reversed
-    file_spaces
-        .into_iter()
-        .zip_longest(files.into_iter().rev())
-        .map_while(|a| match a {
-            // file as well as a free space to the left of it
-            EitherOrBoth::Both(leftmost_free_space, rightmost_file)
-                if leftmost_free_space.pos.0 < rightmost_file.pos.0 =>
-            {
-                Some((leftmost_free_space.pos.0 * rightmost_file.id.0) as u64)
-            }
-            // file but no more free spaces further left of it
-            EitherOrBoth::Right(rightmost_file) | EitherOrBoth::Both(_, rightmost_file) => {
-                Some((rightmost_file.pos.0 * rightmost_file.id.0) as u64)
-            }
-            // free space but no more files to move [ends]
-            EitherOrBoth::Left(_leftmost_free_space) => None,
-        })
-        .sum()
+    let disk_map = input.as_ref().trim().parse::<DiskMap>().expect("parsable");
+    Disk::from(&disk_map).compacted(&BlockLevel).checksum()
 }
 
 #[cfg(test)]
@@ -12,7 +12,8 @@ use winnow::{
 };
 
 fn main() {
-	util::DayInput::find::<_>().solve_with(solve);
+	// doesn't compile until the package is renamed to `dayN`
+	util::day_input!().solve_with(solve);
 }
 
 fn solve(input: impl AsRef<str>) -> u64 {
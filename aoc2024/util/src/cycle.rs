@@ -0,0 +1,194 @@
+//! Finding when a repeatedly stepped state starts repeating, to skip ahead to far away steps.
+//!
+//! Every sequence `x0, x1 = f(x0), x2 = f(x1), ...` over a finite amount of states eventually
+//! repeats: after some `prefix` of steps, it goes through the same `length` states over and over.
+//! Knowing both, step `n` is the same state as step [`Cycle::equivalent`]`(n)`.
+//!
+//! - [`floyd`] and [`brent`] only keep a couple of states around, but need to compare states.
+//! - [`with_history`] remembers a fingerprint of every state, but stops as soon as the first
+//!   repeat is stepped, and keeps every state so far to look up any step afterwards.
+
+use std::collections::{hash_map, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cycle {
+    /// Steps before the first state that repeats.
+    pub prefix: usize,
+    /// Steps until a state repeats, at least 1.
+    pub length: usize,
+}
+impl Cycle {
+    /// Earliest step with the same state as step `n`, below `prefix + length`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+}
+
+/// Tortoise and hare: the hare steps twice as fast, until both meet somewhere in the cycle.
+/// Never terminates if states never repeat.
+pub fn floyd<S: Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let ahead = step(&hare);
+        hare = step(&ahead);
+    }
+    // the meeting point is a multiple of the length away from the start,
+    // so stepping both at the same speed, they meet again at the start of the cycle
+    let mut prefix = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { prefix, length }
+}
+
+/// Like [`floyd`], but with fewer steps: the hare searches for the length directly,
+/// teleporting the tortoise to itself at every power of two.
+/// Never terminates if states never repeat.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // a hare `length` steps ahead meets the tortoise exactly at the start of the cycle
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, length }
+}
+
+/// Every state until the first repeat, with the cycle they form, or `None` if no state repeats
+/// in the first `limit` steps. States are told apart by their `fingerprint`, which can be
+/// cheaper to hash than the whole state (e.g. only the parts which decide what comes next).
+pub fn with_history<S, K: Hash + Eq>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    mut fingerprint: impl FnMut(&S) -> K,
+    limit: usize,
+) -> (Vec<S>, Option<Cycle>) {
+    let mut seen = HashMap::from([(fingerprint(&start), 0)]);
+    let mut history = vec![start];
+    for n in 1..=limit {
+        let next = step(&history[n - 1]);
+        match seen.entry(fingerprint(&next)) {
+            hash_map::Entry::Occupied(entry) => {
+                let prefix = *entry.get();
+                return (
+                    history,
+                    Some(Cycle {
+                        prefix,
+                        length: n - prefix,
+                    }),
+                );
+            }
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(n);
+            }
+        }
+        history.push(next);
+    }
+    (history, None)
+}
+
+/// State after `n` steps, without stepping more than `prefix + length` times.
+pub fn nth<S: Clone + Hash + Eq>(start: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    nth_by_key(start, step, S::clone, n)
+}
+
+/// Like [`nth`], telling states apart by their `fingerprint`.
+pub fn nth_by_key<S, K: Hash + Eq>(
+    start: S,
+    step: impl FnMut(&S) -> S,
+    fingerprint: impl FnMut(&S) -> K,
+    n: usize,
+) -> S {
+    let (mut history, cycle) = with_history(start, step, fingerprint, n);
+    let index = cycle.map_or(n, |cycle| cycle.equivalent(n));
+    history.swap_remove(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_detectors_agree() {
+        for prefix in 0..6 {
+            for length in 1..8 {
+                // 0, 1, ..., prefix + length - 1, then back to prefix
+                let step = |&x: &usize| {
+                    if x + 1 < prefix + length {
+                        x + 1
+                    } else {
+                        prefix
+                    }
+                };
+                let expected = Cycle { prefix, length };
+                assert_eq!(floyd(0, step), expected);
+                assert_eq!(brent(0, step), expected);
+                assert_eq!(with_history(0, step, |&x| x, 100).1, Some(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn skips_to_far_steps() {
+        // 10 → 5 → 16 → 8 → 4 → 2 → 1 → 4 → ...
+        let collatz = |&x: &u64| if x % 2 == 0 { x / 2 } else { 3 * x + 1 };
+        let cycle = brent(10, collatz);
+        assert_eq!(
+            cycle,
+            Cycle {
+                prefix: 4,
+                length: 3
+            }
+        );
+        assert_eq!(cycle.equivalent(2), 2);
+        assert_eq!(cycle.equivalent(1_000_000_000), 4);
+        assert_eq!(cycle.equivalent(1_000_000_001), 5);
+        assert_eq!(nth(10, collatz, 1_000_000_001), 2);
+        assert_eq!(nth(10, collatz, 3), 8);
+        // the amount of steps taken does not decide what comes next
+        let counted = |&(x, steps): &(u64, usize)| (collatz(&x), steps + 1);
+        assert_eq!(nth_by_key((10, 0), counted, |s| s.0, 1_000_000_001), (2, 5));
+    }
+
+    #[test]
+    fn gives_up_after_limit() {
+        let (history, cycle) = with_history(0, |&x| x + 1, |&x| x, 10);
+        assert_eq!(cycle, None);
+        assert_eq!(history.len(), 11);
+        assert_eq!(nth(0, |&x| x + 1, 1000), 1000);
+    }
+}
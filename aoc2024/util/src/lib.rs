@@ -1,14 +1,22 @@
 #![allow(unused)]
 
+pub mod cycle;
+pub mod random;
+
 /// Input that a solver can take.
 pub struct DayInput<S: AsRef<str>>(S);
 impl DayInput<String> {
     /// Tries finding the relevant input, either by accepting a string or
     /// falling back to some maybe-existing file.
     /// The `inputs` folder may be a private submodule.
+    /// Prefer [`day_input`], which gets `DAY` right.
+    /// Flags (see [`has_flag`]) are not paths, wherever they are given.
     pub fn find<const DAY: u8>() -> Self {
         let absolute = {
-            let relative_path = std::env::args().nth(1).unwrap_or_else(|| {
+            let path = std::env::args()
+                .skip(1)
+                .find(|arg| parse_flag(arg).is_none());
+            let relative_path = path.unwrap_or_else(|| {
                 let default = format!("inputs/{}", DAY);
                 eprintln!("No path specified, assuming you want '{}'.", default);
                 default
@@ -34,6 +42,70 @@ impl AsRef<str> for DayInput<String> {
     }
 }
 
+/// Name and value of a `--name` or `--name=value` argument, or `None` for other arguments.
+fn parse_flag(arg: &str) -> Option<(&str, Option<&str>)> {
+    let flag = arg.strip_prefix("--")?;
+    Some(match flag.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (flag, None),
+    })
+}
+
+/// Whether `--name` (or `--name=value`) was given, before or after the input path.
+pub fn has_flag(name: &str) -> bool {
+    std::env::args()
+        .skip(1)
+        .any(|arg| parse_flag(&arg).is_some_and(|(flag, _)| flag == name))
+}
+
+/// Value of `--name=value`, if given, before or after the input path.
+pub fn flag_value(name: &str) -> Option<String> {
+    std::env::args()
+        .skip(1)
+        .find_map(|arg| match parse_flag(&arg) {
+            Some((flag, value)) if flag == name => value.map(str::to_owned),
+            _ => None,
+        })
+}
+
+/// Day of a package named `dayN`, failing to compile (in a const context) for other names.
+pub const fn day_of_package(name: &str) -> u8 {
+    let bytes = name.as_bytes();
+    assert!(
+        matches!(bytes, [b'd', b'a', b'y', _] | [b'd', b'a', b'y', _, _]),
+        "package should be named `dayN`"
+    );
+    let mut day = 0;
+    let mut i = 3;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii_digit(), "package should be named `dayN`");
+        day = day * 10 + (bytes[i] - b'0');
+        i += 1;
+    }
+    assert!(matches!(day, 1..=25), "day should be between 1 and 25");
+    day
+}
+
+/// [`DayInput::find`] for the day of the calling package, named `dayN`.
+/// Naming the day explicitly still works, but doesn't compile if it's a different day:
+/// ```ignore
+/// util::day_input!().solve_with(solve);
+/// util::day_input!(9).solve_with(solve);
+/// ```
+#[macro_export]
+macro_rules! day_input {
+    () => {
+        $crate::DayInput::find::<{ $crate::day_of_package(env!("CARGO_PKG_NAME")) }>()
+    };
+    ($day:expr) => {{
+        const _: () = assert!(
+            $crate::day_of_package(env!("CARGO_PKG_NAME")) == $day,
+            "day does not match the package name"
+        );
+        $crate::DayInput::find::<{ $day }>()
+    }};
+}
+
 // dev-only prints
 #[cfg(debug_assertions)]
 macro_rules! dprintln {
@@ -49,3 +121,42 @@ macro_rules! dprintln {
 use std::fmt::Display;
 
 pub(crate) use dprintln;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_told_apart_from_paths() {
+        assert_eq!(parse_flag("--show"), Some(("show", None)));
+        assert_eq!(parse_flag("--size=11,7"), Some(("size", Some("11,7"))));
+        assert_eq!(parse_flag("--x="), Some(("x", Some(""))));
+        assert_eq!(parse_flag("inputs/14"), None);
+        assert_eq!(parse_flag("-v"), None);
+    }
+
+    #[test]
+    fn days_from_package_names() {
+        assert_eq!(day_of_package("day1"), 1);
+        assert_eq!(day_of_package("day9"), 9);
+        assert_eq!(day_of_package("day25"), 25);
+    }
+
+    #[test]
+    #[should_panic(expected = "named `dayN`")]
+    fn other_packages_rejected() {
+        day_of_package("util");
+    }
+
+    #[test]
+    #[should_panic(expected = "named `dayN`")]
+    fn template_rejected() {
+        day_of_package("day_template");
+    }
+
+    #[test]
+    #[should_panic(expected = "between 1 and 25")]
+    fn days_past_christmas_rejected() {
+        day_of_package("day26");
+    }
+}
@@ -0,0 +1,49 @@
+//! Small reproducible random numbers, for tests and checks that need many arbitrary inputs.
+//! A xorshift generator: not good enough for anything statistical, but the same seed always
+//! gives the same numbers, so a failing input can be found again.
+
+#[derive(Debug, Clone)]
+pub struct XorShift(u64);
+
+impl Default for XorShift {
+    fn default() -> Self {
+        Self::new(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+impl XorShift {
+    /// Generator starting from `seed`, which can't be zero since zero only shifts to zero.
+    pub fn new(seed: u64) -> Self {
+        assert_ne!(seed, 0, "xorshift seed should not be zero");
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XorShift;
+
+    #[test]
+    fn seeds_repeat() {
+        let numbers = |seed| {
+            let mut random = XorShift::new(seed);
+            (0..5).map(|_| random.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        let mut random = XorShift::default();
+        assert!((0..100).all(|_| random.below(6) < 6));
+    }
+}
@@ -29,6 +29,8 @@ impl FromStr for Puzzle {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
     use shared::{EXAMPLE_INPUT, REAL_INPUT};
 
@@ -44,6 +46,19 @@ mod tests {
         );
     }
 
+    // /// Naive reference solver, obviously correct but slow.
+    // fn brute_force(entries: &[Entry]) -> Solution {
+    //     todo!("reference solver")
+    // }
+    //
+    // proptest! {
+    //     #[test]
+    //     fn solver_matches_brute_force(entries in prop::collection::vec(any::<Entry>(), 0..100)) {
+    //         let expected = brute_force(&entries);
+    //         prop_assert_eq!(Solution::from(Puzzle(entries)), expected);
+    //     }
+    // }
+
     // #[ignore]
     #[test]
     fn real_input_works() {
@@ -1,10 +1,34 @@
 pub const REAL_INPUT: &str = include_str!("../../inputs/{{project-name}}");
 pub const EXAMPLE_INPUT: &str = todo!();
 
+/// `proptest` generators for puzzle input.
+#[cfg(test)]
+pub mod strategies {
+    use proptest::prelude::*;
+
+    // impl Arbitrary for Entry {
+    //     type Parameters = ();
+    //     type Strategy = BoxedStrategy<Self>;
+    //
+    //     fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    //         todo!("generate a single entry of the input")
+    //     }
+    // }
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
+    // proptest! {
+    //     #[test]
+    //     fn display_round_trips(entry: Entry) {
+    //         prop_assert_eq!(entry.to_string().parse::<Entry>(), Ok(entry));
+    //     }
+    // }
+
     // // #[ignore]
     // #[test]
     // fn real_input_assumptions() {}
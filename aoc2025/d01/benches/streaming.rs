@@ -0,0 +1,54 @@
+//! Compares solving through an owned `Vec` of rotations against folding while parsing.
+//!
+//! Both paths share one `FoldSolution` per part, so any difference is down to
+//! allocating the intermediate `Vec`.
+
+// The solvers live in binaries, which cannot be depended on, so they are pulled in by path.
+// Each binary brings its own copy of `shared`, and its tests are stripped, leaving imports unused.
+#![allow(clippy::duplicate_mod, unused_imports)]
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[path = "../src/p1.rs"]
+#[allow(dead_code)]
+mod p1;
+#[path = "../src/p2.rs"]
+#[allow(dead_code)]
+mod p2;
+
+fn part_one(c: &mut Criterion) {
+    use p1::{Password, Puzzle, Rotation, REAL_INPUT};
+
+    let mut group = c.benchmark_group("d01 p1");
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            let puzzle: Puzzle<Rotation> = black_box(REAL_INPUT).parse().expect("able to parse");
+            Password::from(puzzle)
+        })
+    });
+    group.bench_function("streaming", |b| {
+        b.iter(|| Password::try_from(Puzzle::from(black_box(REAL_INPUT))).expect("able to parse"))
+    });
+    group.finish();
+}
+
+fn part_two(c: &mut Criterion) {
+    use p2::{Password, Puzzle, Rotation, REAL_INPUT};
+
+    let mut group = c.benchmark_group("d01 p2");
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            let puzzle: Puzzle<Rotation> = black_box(REAL_INPUT).parse().expect("able to parse");
+            Password::from(puzzle)
+        })
+    });
+    group.bench_function("streaming", |b| {
+        b.iter(|| Password::try_from(Puzzle::from(black_box(REAL_INPUT))).expect("able to parse"))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, part_one, part_two);
+criterion_main!(benches);
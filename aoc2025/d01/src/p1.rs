@@ -1,4 +1,5 @@
-use shared::*;
+pub(crate) use shared::*;
+use util::FoldSolution;
 use winnow::error::ContextError;
 
 mod shared;
@@ -53,21 +54,31 @@ impl std::ops::Add<Rotation> for DialPoint {
     }
 }
 
+/// The fold shared by both puzzle representations.
+impl FoldSolution for Password {
+    type Entry = Rotation;
+    type State = (usize, DialPoint);
+
+    fn initial_state() -> Self::State {
+        (0, DialPoint::default())
+    }
+
+    fn fold_entry(
+        (times_reached_zero, dial_point): Self::State,
+        rotation: Rotation,
+    ) -> Self::State {
+        handle_rotation(times_reached_zero, dial_point, rotation)
+    }
+
+    fn from_state((times_reached_zero, _): Self::State) -> Self {
+        Password(times_reached_zero)
+    }
+}
+
 /// solve for `Vec` of `Rotation`s
 impl From<Puzzle<Rotation>> for Password {
     fn from(puzzle: Puzzle<Rotation>) -> Self {
-        Password(
-            puzzle
-                .0
-                .into_iter()
-                .fold(
-                    (0, DialPoint::default()),
-                    |(times_reached_zero, dial_point), rotation| {
-                        handle_rotation(times_reached_zero, dial_point, rotation)
-                    },
-                )
-                .0,
-        )
+        Password::fold_entries(puzzle.0)
     }
 }
 
@@ -76,17 +87,8 @@ impl From<Puzzle<Rotation>> for Password {
 impl<I: Iterator<Item = RotationParseResult>> TryFrom<Puzzle<RotationParseResult, I>> for Password {
     type Error = ContextError;
 
-    fn try_from(mut puzzle: Puzzle<RotationParseResult, I>) -> Result<Self, Self::Error> {
-        puzzle
-            .0
-            .try_fold::<(usize, DialPoint), _, Result<(usize, DialPoint), ContextError>>(
-                (0, DialPoint::default()),
-                |(times_reached_zero, dial_point), rotation| {
-                    rotation
-                        .map(|rotation| handle_rotation(times_reached_zero, dial_point, rotation))
-                },
-            )
-            .map(|(password, _)| Password(password))
+    fn try_from(puzzle: Puzzle<RotationParseResult, I>) -> Result<Self, Self::Error> {
+        Password::try_fold_entries(puzzle.0)
     }
 }
 
@@ -95,6 +97,7 @@ mod tests {
     use std::str::FromStr;
 
     use itertools::Itertools;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -218,6 +221,35 @@ mod tests {
         assert_eq!(computed_password, EXAMPLE_PASSWORD)
     }
 
+    /// Click-by-click simulation, counting every rotation that ends on zero.
+    fn simulate_clicks(rotations: &[Rotation]) -> Password {
+        let mut dial_point: i64 = 50;
+        let mut times_reached_zero = 0;
+        for rotation in rotations {
+            let step = match rotation.dir {
+                Direction::Left => -1,
+                Direction::Right => 1,
+            };
+            for _ in 0..rotation.distance {
+                dial_point = (dial_point + step).rem_euclid(100);
+            }
+            if dial_point == 0 {
+                times_reached_zero += 1;
+            }
+        }
+        Password(times_reached_zero)
+    }
+
+    proptest! {
+        #[test]
+        fn fold_matches_simulation(rotations in shared::strategies::rotations()) {
+            let input = rotations.iter().join("\n");
+            let streamed = Password::try_from(Puzzle::from(input.as_str())).expect("able to parse");
+            prop_assert_eq!(&streamed, &simulate_clicks(&rotations));
+            prop_assert_eq!(Password::from(Puzzle(rotations)), streamed);
+        }
+    }
+
     // #[ignore]
     #[test]
     fn real_input_works() {
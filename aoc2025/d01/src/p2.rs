@@ -1,5 +1,6 @@
 mod shared;
-use shared::*;
+pub(crate) use shared::*;
+use util::FoldSolution;
 use winnow::error::ContextError;
 
 /// Like previous, but every roll past 0 must count as hitting it,
@@ -10,82 +11,78 @@ fn main() {
     println!("Password is {}", password.0);
 }
 
-fn handle_rotation(
+/// A dial with `SIZE` points (`0..SIZE`), counting every click that lands on zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ZeroCountingDial<const SIZE: usize> {
+    position: usize,
     times_passed_zero: usize,
-    dial_point: DialPoint,
-    rotation: Rotation,
-) -> (usize, DialPoint) {
-    let full_cycles = rotation.distance.div_euclid(100);
-    let cycle_remainder = rotation.distance.rem_euclid(100) as u8;
-    match rotation.dir {
-        // we must handle underflow correctly
-        Direction::Left => match dial_point.0.cmp(&cycle_remainder) {
-            // we move below and past zero
-            std::cmp::Ordering::Less if dial_point.0 != 0 => (
-                times_passed_zero + full_cycles + 1,
-                DialPoint(100 - (cycle_remainder - dial_point.0)),
-            ),
-            // we move below zero, but we were just there
-            std::cmp::Ordering::Less => (
-                times_passed_zero + full_cycles,
-                DialPoint(100 - (cycle_remainder - dial_point.0)),
-            ),
-            // we land on zero
-            std::cmp::Ordering::Equal => (times_passed_zero + full_cycles + 1, DialPoint(0)),
-            // we stay some amount above zero
-            std::cmp::Ordering::Greater => (
-                times_passed_zero + full_cycles,
-                DialPoint(dial_point.0 - cycle_remainder),
-            ),
-        },
-        // no overflow: 99 + 99 < 255
-        Direction::Right => match dial_point.0 + cycle_remainder {
-            // we move beyond 100 (therefor past zero)
-            100.. => (
-                times_passed_zero + full_cycles + 1,
-                DialPoint(dial_point.0 + cycle_remainder - 100),
-            ),
-            // we do not move beyond 100
-            1..=99 => (
-                times_passed_zero + full_cycles,
-                DialPoint(dial_point.0 + cycle_remainder),
-            ),
-            // we do not move (assume overflow impossible)
-            0 => panic!("unexpected edge case"), // (times_passed_zero + full_cycles + 1, dial_point),
-        },
+}
+impl<const SIZE: usize> ZeroCountingDial<SIZE> {
+    fn starting_at(position: usize) -> Self {
+        assert!(position < SIZE, "position must be on the dial");
+        Self {
+            position,
+            times_passed_zero: 0,
+        }
+    }
+
+    /// Turn the dial without simulating each click, so any `distance` takes constant time.
+    fn turn(self, rotation: Rotation) -> Self {
+        // every full turn passes zero exactly once, wherever we start
+        let full_turns = rotation.distance / SIZE;
+        let remainder = rotation.distance % SIZE;
+        // clicks until first landing on zero, a full turn away if we are already there
+        let clicks_to_zero = match rotation.dir {
+            Direction::Left => self.position,
+            Direction::Right => SIZE - self.position,
+        };
+        let clicks_to_zero = if clicks_to_zero == 0 {
+            SIZE
+        } else {
+            clicks_to_zero
+        };
+        // the partial turn passes zero at most once, since it is shorter than a full turn
+        let partial_wrap = usize::from(remainder >= clicks_to_zero);
+        Self {
+            position: match rotation.dir {
+                Direction::Left => (self.position + SIZE - remainder) % SIZE,
+                Direction::Right => (self.position + remainder) % SIZE,
+            },
+            times_passed_zero: self.times_passed_zero + full_turns + partial_wrap,
+        }
+    }
+}
+
+/// The fold shared by both puzzle representations.
+impl FoldSolution for Password {
+    type Entry = Rotation;
+    type State = ZeroCountingDial<100>;
+
+    fn initial_state() -> Self::State {
+        ZeroCountingDial::starting_at(DialPoint::default().0.into())
+    }
+
+    fn fold_entry(dial: Self::State, rotation: Rotation) -> Self::State {
+        dial.turn(rotation)
+    }
+
+    fn from_state(dial: Self::State) -> Self {
+        Password(dial.times_passed_zero)
     }
 }
 
 /// Requires a vector of parsed rotations.
 impl From<Puzzle<Rotation>> for Password {
     fn from(vec: Puzzle<Rotation>) -> Self {
-        Password(
-            vec.0
-                .into_iter()
-                .fold(
-                    (0, DialPoint::default()),
-                    |(times_passed_zero, dial_point), rotation| {
-                        handle_rotation(times_passed_zero, dial_point, rotation)
-                    },
-                )
-                .0,
-        )
+        Password::fold_entries(vec.0)
     }
 }
 /// Direct folding, without needed to allocate while parsing.
 impl<I: Iterator<Item = RotationParseResult>> TryFrom<Puzzle<RotationParseResult, I>> for Password {
     type Error = ContextError;
 
-    fn try_from(mut iter: Puzzle<RotationParseResult, I>) -> Result<Self, Self::Error> {
-        iter.0
-            .try_fold(
-                (0, DialPoint::default()),
-                |(times_passed_zero, dial_point), rotation| {
-                    rotation
-                        .map(|rotation| handle_rotation(times_passed_zero, dial_point, rotation))
-                },
-            )
-            .map(|(times_passed_zero, _)| Password(times_passed_zero))
+    fn try_from(iter: Puzzle<RotationParseResult, I>) -> Result<Self, Self::Error> {
+        Password::try_fold_entries(iter.0)
     }
 }
 
@@ -94,6 +91,7 @@ mod tests {
     use std::str::FromStr;
 
     use itertools::Itertools;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -138,6 +136,94 @@ mod tests {
         assert_eq!(computed_password.0, 6907);
     }
 
+    /// Click-by-click simulation, counting every click that ends on zero.
+    fn simulate_clicks<const SIZE: usize>(start: usize, rotations: &[Rotation]) -> usize {
+        let mut position = start;
+        let mut times_passed_zero = 0;
+        for rotation in rotations {
+            for _ in 0..rotation.distance {
+                position = match rotation.dir {
+                    Direction::Left => (position + SIZE - 1) % SIZE,
+                    Direction::Right => (position + 1) % SIZE,
+                };
+                if position == 0 {
+                    times_passed_zero += 1;
+                }
+            }
+        }
+        times_passed_zero
+    }
+
+    /// Rotations of any length up to `max_distance`, including zero.
+    fn rotations_up_to(max_distance: usize) -> impl Strategy<Value = Vec<Rotation>> {
+        let rotation = (any::<Direction>(), 0..=max_distance)
+            .prop_map(|(dir, distance)| Rotation { dir, distance });
+        prop::collection::vec(rotation, 0..50)
+    }
+
+    fn closed_form<const SIZE: usize>(start: usize, rotations: &[Rotation]) -> usize {
+        rotations
+            .iter()
+            .fold(
+                ZeroCountingDial::<SIZE>::starting_at(start),
+                |dial, &rotation| dial.turn(rotation),
+            )
+            .times_passed_zero
+    }
+
+    proptest! {
+        #[test]
+        fn fold_matches_simulation(rotations in shared::strategies::rotations()) {
+            let input = rotations.iter().join("\n");
+            let streamed = Password::try_from(Puzzle::from(input.as_str())).expect("able to parse");
+            prop_assert_eq!(&streamed, &Password(simulate_clicks::<100>(50, &rotations)));
+            prop_assert_eq!(Password::from(Puzzle(rotations)), streamed);
+        }
+
+        #[test]
+        fn closed_form_matches_simulation_on_single_point_dial(rotations in rotations_up_to(300)) {
+            prop_assert_eq!(closed_form::<1>(0, &rotations), simulate_clicks::<1>(0, &rotations));
+        }
+
+        #[test]
+        fn closed_form_matches_simulation_on_small_dial(start in 0..7usize, rotations in rotations_up_to(30)) {
+            prop_assert_eq!(closed_form::<7>(start, &rotations), simulate_clicks::<7>(start, &rotations));
+        }
+
+        #[test]
+        fn closed_form_matches_simulation_on_large_dial(start in 0..1000usize, rotations in rotations_up_to(5000)) {
+            prop_assert_eq!(closed_form::<1000>(start, &rotations), simulate_clicks::<1000>(start, &rotations));
+        }
+    }
+
+    #[test]
+    fn huge_distances_work() {
+        let rotations = [
+            Rotation {
+                dir: Direction::Right,
+                distance: 10usize.pow(15),
+            },
+            Rotation {
+                dir: Direction::Left,
+                distance: 10usize.pow(15) + 50,
+            },
+        ];
+        // the first passes zero every 100 clicks after 50, and ends at 50 again,
+        // the second does the same, and then lands on zero
+        assert_eq!(closed_form::<100>(50, &rotations), 2 * 10usize.pow(13) + 1);
+    }
+
+    #[test]
+    fn full_cycles_from_zero_work() {
+        let computed_password =
+            Password::try_from(Puzzle::from("L50\nL100\nR200\nL1")).expect("able to parse");
+        assert_eq!(
+            computed_password,
+            Password(4),
+            "one landing plus three full cycles"
+        );
+    }
+
     #[test]
     fn outside_sample_works() {
         const SAMPLE: &str = "R9\nL8\nL26\nR45\nR40\nL45\nR13\nL20\nL8\nR5\nL390\nR47\nR38\nL22\nL26\nL22\nL19\nL25\nL21\nL15\nR19\nR3\nR33\nR46\nL9\nR48\nL21\nR13\nR10\nL20\nR48\nL43\nR44\nR35\nR23\nR86\nL21\nL7\nR26\nL23\nL50\nL34\nR22\nL14\nR899\nR21\nL47\nR16\nL14\nL1";
@@ -55,7 +55,7 @@ impl Default for DialPoint {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Rotation {
     /// Left or Right
     pub dir: Direction,
@@ -75,7 +75,7 @@ impl FromStr for Rotation {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
@@ -126,10 +126,72 @@ pub mod display {
     }
 }
 
+/// `proptest` generators for puzzle input.
+#[cfg(test)]
+pub mod strategies {
+    use proptest::prelude::*;
+
+    use super::{Direction, Rotation};
+
+    impl Arbitrary for Direction {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            prop_oneof![Just(Direction::Left), Just(Direction::Right)].boxed()
+        }
+    }
+
+    impl Arbitrary for Rotation {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            let distance = prop_oneof![
+                // same bound as assumed for the real input
+                8 => 1..=999usize,
+                // full turns are the interesting edge cases
+                2 => (1..=9usize).prop_map(|turns| turns * 100),
+            ];
+            (any::<Direction>(), distance)
+                .prop_map(|(dir, distance)| Rotation { dir, distance })
+                .boxed()
+        }
+    }
+
+    /// A list of rotations, like the lines of an input.
+    pub fn rotations() -> impl Strategy<Value = Vec<Rotation>> {
+        prop::collection::vec(any::<Rotation>(), 0..100)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
     use super::*;
 
+    proptest! {
+        #[test]
+        fn rotation_display_round_trips(rotation: Rotation) {
+            prop_assert_eq!(rotation.to_string().parse::<Rotation>(), Ok(rotation));
+        }
+
+        #[test]
+        fn puzzle_display_round_trips(rotations in strategies::rotations()) {
+            let input = rotations.iter().join("\n");
+            let owned = input.parse::<Puzzle<Rotation>>().expect("able to parse").0;
+            prop_assert_eq!(&owned, &rotations);
+
+            let streamed = Puzzle::from(input.as_str())
+                .0
+                .collect::<Result<Vec<_>, _>>()
+                .expect("able to parse");
+            prop_assert_eq!(&streamed, &rotations);
+        }
+    }
+
     // #[ignore]
     #[test]
     fn real_input_assumptions() {
@@ -1,5 +1,5 @@
 mod shared;
-use shared::{digits, Puzzle, Solution, REAL_INPUT};
+use shared::{Puzzle, Solution, REAL_INPUT};
 
 fn main() {
     let parsed = REAL_INPUT.parse::<Puzzle>().expect("able to parse input");
@@ -14,31 +14,26 @@ impl From<Puzzle> for Solution {
         // - 99 is invalid,
         // - 8989 is invalid,
         // - 312312 is invalid.
-        Solution(
-            puzzle
-                .0
-                .iter()
-                .flat_map(|r| r.0.clone())
-                .filter(|&n| {
-                    let digits = digits(n);
-                    let res = digits.len().is_multiple_of(2)
-                        && digits
-                            .split_at_checked(digits.len() / 2)
-                            .map(|(a, b)| a == b)
-                            .expect("always in the middle");
-                    if res {
-                        eprintln!("{n} is an invalid ID!");
-                    }
-                    res
-                })
-                .sum(),
-        )
+        // Only IDs with an even digit count can be split in two, so we sum those per digit count.
+        let sum: u128 = puzzle
+            .0
+            .iter()
+            .flat_map(|r| {
+                r.digit_counts()
+                    .filter(|digit_count| digit_count.is_multiple_of(2))
+                    .map(|digit_count| r.repeated_block_sum(digit_count / 2, 2))
+            })
+            .sum();
+        Solution(sum.try_into().expect("sum fits in u64"))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use shared::digits;
 
     const EXAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
     const EXAMPLE_SOLUTION: Solution = Solution(1227775554);
@@ -53,6 +48,43 @@ mod tests {
         assert_eq!(solution, EXAMPLE_SOLUTION);
     }
 
+    /// Iterates every ID, checking its digits directly.
+    fn brute_force(puzzle: &Puzzle) -> Solution {
+        Solution(
+            puzzle
+                .0
+                .iter()
+                .flat_map(|r| r.0.clone())
+                .filter(|&n| {
+                    let digits = digits(n);
+                    digits.len().is_multiple_of(2)
+                        && digits
+                            .split_at_checked(digits.len() / 2)
+                            .map(|(a, b)| a == b)
+                            .expect("always in the middle")
+                })
+                .sum(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn arithmetic_matches_brute_force(puzzle: Puzzle) {
+            let expected = brute_force(&puzzle);
+            prop_assert_eq!(Solution::from(puzzle), expected);
+        }
+    }
+
+    #[test]
+    fn wide_ranges_work() {
+        let parsed = "1-9999".parse::<Puzzle>().expect("able to parse");
+        assert_eq!(Solution::from(parsed), Solution(495 + 101 * 4905));
+
+        // every digit count up to 12, far too many IDs to iterate
+        let parsed = "1-999999999999".parse::<Puzzle>().expect("able to parse");
+        assert_eq!(Solution::from(parsed), Solution(495_495_540_949_540_950));
+    }
+
     // #[ignore]
     #[test]
     fn real_input_works() {
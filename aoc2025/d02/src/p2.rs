@@ -1,6 +1,6 @@
 mod shared;
 use itertools::Itertools;
-use shared::{digits, Puzzle, Solution, REAL_INPUT};
+use shared::{ProductIdRange, Puzzle, Solution, REAL_INPUT};
 
 fn main() {
     let parsed = REAL_INPUT.parse::<Puzzle>().expect("able to parse input");
@@ -16,29 +16,64 @@ impl From<Puzzle> for Solution {
         // - 8989 is invalid,
         // - 312312 is invalid.
         // - 121212 is invalid. (*new for p2*)
-        Solution(
-            puzzle
-                .0
-                .iter()
-                .flat_map(|r| r.0.clone())
-                .filter(|&n| {
-                    let digits = digits(n);
-                    let max_sequence_len = digits.len().div_euclid(2);
-                    let res = (1..=max_sequence_len)
-                        .any(|sequence_len| digits.chunks(sequence_len).all_equal());
-                    if res {
-                        eprintln!("{n} is an invalid ID!");
-                    }
-                    res
-                })
-                .sum(),
-        )
+        let sum: i128 = puzzle
+            .0
+            .iter()
+            .flat_map(|r| {
+                r.digit_counts()
+                    .map(|digit_count| invalid_id_sum(r, digit_count))
+            })
+            .sum();
+        Solution(sum.try_into().expect("sum fits in u64"))
     }
 }
 
+/// Sum of invalid IDs in range with exactly `digit_count` digits.
+///
+/// Repeating a block `r` times also repeats a block `r * s` times whenever that still divides the digit count
+/// (`12121212` is both `1212` twice and `12` four times), so the sets for different repeat counts overlap.
+/// It is enough to consider the repeat counts that are primes dividing the digit count,
+/// and inclusion–exclusion over those removes the overlaps: IDs repeating blocks both
+/// `p` and `q` times are exactly those repeating a block `p * q` times.
+fn invalid_id_sum(range: &ProductIdRange, digit_count: u32) -> i128 {
+    prime_factors(digit_count)
+        .into_iter()
+        .powerset()
+        .skip(1) // the empty set is not a repeat count
+        .map(|primes| {
+            let repeats: u32 = primes.iter().product();
+            let sum = range.repeated_block_sum(digit_count / repeats, repeats) as i128;
+            if primes.len() % 2 == 1 {
+                sum
+            } else {
+                -sum
+            }
+        })
+        .sum()
+}
+
+/// Distinct prime factors, in increasing order.
+fn prime_factors(mut n: u32) -> Vec<u32> {
+    let mut factors = Vec::new();
+    let mut factor = 2;
+    while n > 1 {
+        if n.is_multiple_of(factor) {
+            factors.push(factor);
+            while n.is_multiple_of(factor) {
+                n /= factor;
+            }
+        }
+        factor += 1;
+    }
+    factors
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use shared::digits;
 
     const EXAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
     const EXAMPLE_SOLUTION: Solution = Solution(4174379265);
@@ -53,6 +88,48 @@ mod tests {
         assert_eq!(solution, EXAMPLE_SOLUTION);
     }
 
+    /// Iterates every ID, checking its digits directly.
+    fn brute_force(puzzle: &Puzzle) -> Solution {
+        Solution(
+            puzzle
+                .0
+                .iter()
+                .flat_map(|r| r.0.clone())
+                .filter(|&n| {
+                    let digits = digits(n);
+                    let max_sequence_len = digits.len().div_euclid(2);
+                    (1..=max_sequence_len)
+                        .any(|sequence_len| digits.chunks(sequence_len).all_equal())
+                })
+                .sum(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn arithmetic_matches_brute_force(puzzle: Puzzle) {
+            let expected = brute_force(&puzzle);
+            prop_assert_eq!(Solution::from(puzzle), expected);
+        }
+    }
+
+    #[test]
+    fn prime_factors_work() {
+        assert_eq!(prime_factors(1), vec![]);
+        assert_eq!(prime_factors(6), vec![2, 3]);
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(prime_factors(30), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn wide_ranges_work() {
+        let parsed = "1-999999".parse::<Puzzle>().expect("able to parse");
+        assert_eq!(
+            Solution::from(parsed),
+            brute_force(&"1-999999".parse().expect("able to parse"))
+        );
+    }
+
     // #[ignore]
     #[test]
     fn real_input_works() {
@@ -1,4 +1,5 @@
-use std::{ops::RangeInclusive, str::FromStr};
+use itertools::Itertools;
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
 use winnow::{
     self,
     ascii::dec_uint,
@@ -8,6 +9,7 @@ use winnow::{
 
 pub(crate) const REAL_INPUT: &str = include_str!("../../inputs/d02");
 
+#[cfg(test)]
 pub(crate) fn digits(mut x: u64) -> Vec<u8> {
     let mut digits = Vec::new();
     while x > 0 {
@@ -21,6 +23,7 @@ pub(crate) fn digits(mut x: u64) -> Vec<u8> {
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct Solution(pub u64);
 
+#[derive(Debug, PartialEq, Eq)]
 pub(crate) struct Puzzle(pub Vec<ProductIdRange>);
 impl FromStr for Puzzle {
     type Err = winnow::error::ContextError;
@@ -29,6 +32,11 @@ impl FromStr for Puzzle {
         parse_puzzle(&mut s)
     }
 }
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().join(","))
+    }
+}
 pub(crate) fn parse_puzzle(input: &mut &str) -> WResult<Puzzle> {
     separated(0.., parse_product_id_range, ',')
         .parse_next(input)
@@ -36,10 +44,94 @@ pub(crate) fn parse_puzzle(input: &mut &str) -> WResult<Puzzle> {
 }
 
 /// Assume product ranges are always increasing
+#[derive(Debug, PartialEq, Eq)]
 pub(crate) struct ProductIdRange(pub RangeInclusive<u64>);
 
+impl ProductIdRange {
+    /// Every digit count that an ID in this range can have.
+    pub(crate) fn digit_counts(&self) -> RangeInclusive<u32> {
+        let digit_count = |n: u64| n.checked_ilog10().map_or(1, |log| log + 1);
+        digit_count(*self.0.start())..=digit_count(*self.0.end())
+    }
+
+    /// Sum of every ID in range that is a `block_len`-digit block repeated `repeats` times.
+    ///
+    /// Such IDs are exactly `block * multiplier`, where the multiplier has a one every `block_len` digits
+    /// (e.g. `1001` for two three-digit blocks). The blocks that land in range are consecutive,
+    /// so they are summed as an arithmetic series instead of being iterated.
+    pub(crate) fn repeated_block_sum(&self, block_len: u32, repeats: u32) -> u128 {
+        let multiplier: u128 = (0..repeats).map(|i| 10u128.pow(i * block_len)).sum();
+        let first_block = u128::from(*self.0.start())
+            .div_ceil(multiplier)
+            .max(10u128.pow(block_len - 1));
+        let last_block = (u128::from(*self.0.end()) / multiplier).min(10u128.pow(block_len) - 1);
+        if first_block > last_block {
+            return 0;
+        }
+        multiplier * (first_block + last_block) * (last_block - first_block + 1) / 2
+    }
+}
+
 pub(crate) fn parse_product_id_range(input: &mut &str) -> WResult<ProductIdRange> {
     separated_pair(dec_uint, '-', dec_uint)
         .parse_next(input)
         .map(|(a, b)| ProductIdRange(a..=b))
 }
+impl Display for ProductIdRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.0.start(), self.0.end())
+    }
+}
+
+/// `proptest` generators for puzzle input.
+#[cfg(test)]
+pub(crate) mod strategies {
+    use proptest::prelude::*;
+
+    use super::{ProductIdRange, Puzzle};
+
+    impl Arbitrary for ProductIdRange {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            // pick the digit count first, so short and long IDs are equally likely
+            (1..=12u32)
+                .prop_flat_map(|digit_count| 10u64.pow(digit_count - 1)..10u64.pow(digit_count))
+                .prop_flat_map(|start| (Just(start), 0..=1000u64))
+                .prop_map(|(start, width)| ProductIdRange(start..=start + width))
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for Puzzle {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            prop::collection::vec(any::<ProductIdRange>(), 0..20)
+                .prop_map(Puzzle)
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn puzzle_display_round_trips(puzzle: Puzzle) {
+            prop_assert_eq!(puzzle.to_string().parse::<Puzzle>(), Ok(puzzle));
+        }
+
+        #[test]
+        fn digits_match_decimal_string(n in 1..u64::MAX) {
+            let expected = n.to_string().bytes().map(|b| b - b'0').collect::<Vec<_>>();
+            prop_assert_eq!(digits(n), expected);
+        }
+    }
+}
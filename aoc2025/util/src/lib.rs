@@ -0,0 +1,88 @@
+//! Shared infrastructure for every day.
+
+/// A solution that is computed by folding over puzzle entries one at a time.
+///
+/// Implementing this once lets the same solver run over an owned `Vec` of parsed entries,
+/// as well as over a lazily-parsed iterator that is never collected.
+pub trait FoldSolution: Sized {
+    /// A single parsed entry of the puzzle input (usually a line).
+    type Entry;
+    /// Everything that must be remembered between entries.
+    type State;
+
+    /// State before any entry has been seen.
+    fn initial_state() -> Self::State;
+
+    /// Fold a single entry into the state.
+    fn fold_entry(state: Self::State, entry: Self::Entry) -> Self::State;
+
+    /// Extract the solution once every entry has been seen.
+    fn from_state(state: Self::State) -> Self;
+
+    /// Solve for entries that are already parsed.
+    fn fold_entries(entries: impl IntoIterator<Item = Self::Entry>) -> Self {
+        Self::from_state(
+            entries
+                .into_iter()
+                .fold(Self::initial_state(), Self::fold_entry),
+        )
+    }
+
+    /// Solve while parsing, stopping at the first entry that failed to parse.
+    fn try_fold_entries<E>(
+        entries: impl IntoIterator<Item = Result<Self::Entry, E>>,
+    ) -> Result<Self, E> {
+        entries
+            .into_iter()
+            .try_fold(Self::initial_state(), |state, entry| {
+                entry.map(|entry| Self::fold_entry(state, entry))
+            })
+            .map(Self::from_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums numbers, the simplest possible fold.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Sum(u32);
+    impl FoldSolution for Sum {
+        type Entry = u32;
+        type State = u32;
+
+        fn initial_state() -> Self::State {
+            0
+        }
+
+        fn fold_entry(state: Self::State, entry: Self::Entry) -> Self::State {
+            state + entry
+        }
+
+        fn from_state(state: Self::State) -> Self {
+            Sum(state)
+        }
+    }
+
+    #[test]
+    fn owned_and_streaming_agree() {
+        const INPUT: &str = "1\n2\n3\n4";
+        let owned = INPUT
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<u32>, _>>()
+            .expect("able to parse");
+        assert_eq!(Sum::fold_entries(owned), Sum(10));
+        assert_eq!(
+            Sum::try_fold_entries(INPUT.lines().map(str::parse)),
+            Ok(Sum(10))
+        );
+    }
+
+    #[test]
+    fn streaming_stops_at_parse_error() {
+        let solution = Sum::try_fold_entries("1\nx\n3".lines().map(str::parse::<u32>));
+        assert!(solution.is_err(), "second line is not a number");
+    }
+}